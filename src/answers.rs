@@ -0,0 +1,244 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{Answer, Part};
+
+/// Answers known for a day, from a section of an answers file:
+///
+/// ```toml
+/// [d07]
+/// part1 = 95437
+/// part2 = 24933642
+/// ```
+///
+/// Values are integers or double quoted strings. Either part may be left
+/// out.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Expected {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+impl Expected {
+    /// Differences between `answer` and the parts known, leaving out the
+    /// parts skipped.
+    pub fn check<T: Display, T2: Display>(
+        &self,
+        answer: &Answer<Part<T>, Part<T2>>,
+    ) -> Vec<Mismatch> {
+        let part1 = Mismatch::check(1, &answer.part1, self.part1.as_deref());
+        let part2 = Mismatch::check(2, &answer.part2, self.part2.as_deref());
+        part1.into_iter().chain(part2).collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub part: u32,
+    pub found: String,
+    pub expected: String,
+}
+impl Mismatch {
+    fn check<T: Display>(part: u32, answer: &Part<T>, expected: Option<&str>) -> Option<Mismatch> {
+        let (Part::Solved(found), Some(expected)) = (answer, expected) else {
+            return None;
+        };
+        let found = found.to_string();
+        (found != expected).then(|| Mismatch {
+            part,
+            found,
+            expected: expected.to_string(),
+        })
+    }
+}
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Part {} is {:?}, expected {:?}",
+            self.part, self.found, self.expected
+        )
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum AnswersError {
+    #[error("Error reading answers {0}: {1}")]
+    Read(PathBuf, io::Error),
+    #[error("Line {line} of the answers: {reason}: {text:?}")]
+    Syntax {
+        line: usize,
+        reason: &'static str,
+        text: String,
+    },
+    #[error("No answers for day {day} in {path}")]
+    MissingDay { day: u32, path: PathBuf },
+}
+impl From<AnswersError> for crate::Error {
+    fn from(value: AnswersError) -> Self {
+        crate::Error::Parsing(value.into())
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("{}", .0.iter().map(Mismatch::to_string).collect::<Vec<_>>().join(", "))]
+pub struct WrongAnswer(pub Vec<Mismatch>);
+impl From<WrongAnswer> for crate::Error {
+    fn from(value: WrongAnswer) -> Self {
+        crate::Error::Semantic(value.into())
+    }
+}
+
+/// Answers known for every day in the contents of an answers file, by
+/// section name.
+pub fn parse(text: &str) -> Result<HashMap<String, Expected>, AnswersError> {
+    let mut answers = HashMap::<String, Expected>::new();
+    let mut section = None;
+
+    for (idx, line) in text.lines().enumerate() {
+        let error = |reason| AnswersError::Syntax {
+            line: idx + 1,
+            reason,
+            text: line.to_string(),
+        };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[') {
+            let name = name.strip_suffix(']').ok_or(error("Unclosed section"))?;
+            section = Some(name.trim().to_string());
+            answers.entry(name.trim().to_string()).or_default();
+            continue;
+        }
+
+        let (key, value) = line.split_once('=').ok_or(error("Expected key = value"))?;
+        let value = parse_value(value.trim()).ok_or(error("Bad value"))?;
+        let name = section.as_ref().ok_or(error("Answer outside a section"))?;
+        let expected = answers.get_mut(name).unwrap();
+        match key.trim() {
+            "part1" => expected.part1 = Some(value),
+            "part2" => expected.part2 = Some(value),
+            _ => return Err(error("Unknown key")),
+        }
+    }
+
+    Ok(answers)
+}
+
+fn parse_value(value: &str) -> Option<String> {
+    if let Some(quoted) = value.strip_prefix('"') {
+        let quoted = quoted.strip_suffix('"')?;
+        return (!quoted.contains('"')).then(|| quoted.to_string());
+    }
+
+    value.parse::<i64>().ok()?;
+    Some(value.to_string())
+}
+
+/// Answers known for `day` in the answers file at `path`.
+pub fn load(path: &Path, day: u32) -> Result<Expected, AnswersError> {
+    let text = std::fs::read_to_string(path).map_err(|e| AnswersError::Read(path.into(), e))?;
+    parse(&text)?
+        .remove(&format!("d{day:02}"))
+        .ok_or(AnswersError::MissingDay {
+            day,
+            path: path.into(),
+        })
+}
+
+/// Answers file next to the inputs, for the examples or the full inputs.
+pub fn path(example: bool) -> &'static Path {
+    match example {
+        true => Path::new("input/examples/answers.toml"),
+        false => Path::new("input/full/answers.toml"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_answers() {
+        let answers = parse(
+            "# Known answers\n[d05]\npart1 = \"CMZ\"\npart2 = \"MCD\"\n\n[d07]\npart1 = 95437\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            answers["d05"],
+            Expected {
+                part1: Some("CMZ".to_string()),
+                part2: Some("MCD".to_string()),
+            }
+        );
+        assert_eq!(answers["d07"].part1.as_deref(), Some("95437"));
+        assert_eq!(answers["d07"].part2, None);
+
+        let error = |text| parse(text).unwrap_err().to_string();
+        assert_eq!(
+            error("[d01]\npart3 = 1"),
+            "Line 2 of the answers: Unknown key: \"part3 = 1\""
+        );
+        assert_eq!(
+            error("part1 = 1"),
+            "Line 1 of the answers: Answer outside a section: \"part1 = 1\""
+        );
+        assert_eq!(
+            error("[d01\npart1 = 1"),
+            "Line 1 of the answers: Unclosed section: \"[d01\""
+        );
+        assert_eq!(
+            error("[d01]\npart1 = CMZ"),
+            "Line 2 of the answers: Bad value: \"part1 = CMZ\""
+        );
+    }
+
+    #[test]
+    fn check() {
+        let expected = Expected {
+            part1: Some("24000".to_string()),
+            part2: None,
+        };
+        let answer = |part1| Answer {
+            part1: Part::Solved(part1),
+            part2: Part::Solved(1),
+        };
+
+        assert!(expected.check(&answer(24000)).is_empty());
+        let wrong = expected.check(&answer(24001));
+        assert_eq!(
+            WrongAnswer(wrong).to_string(),
+            "Part 1 is \"24001\", expected \"24000\""
+        );
+
+        let skipped = Answer {
+            part1: Part::<u32>::Skipped,
+            part2: Part::Solved(1),
+        };
+        assert!(expected.check(&skipped).is_empty());
+    }
+
+    #[test]
+    fn examples() {
+        let path = path(true);
+        let expected = load(path, 7).unwrap();
+        assert_eq!(expected.part1.as_deref(), Some("95437"));
+        assert_eq!(expected.part2.as_deref(), Some("24933642"));
+        assert!(load(path, 10).unwrap().part2.is_none());
+
+        assert!(matches!(
+            load(path, 25),
+            Err(AnswersError::MissingDay { day: 25, .. })
+        ));
+        assert!(matches!(
+            load(Path::new("input/missing"), 1),
+            Err(AnswersError::Read(..))
+        ));
+    }
+}
@@ -0,0 +1,128 @@
+use std::{
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use aoc::{
+    days::{Solve, DAYS},
+    Params,
+};
+
+/// How running a day went.
+#[derive(Debug, PartialEq, Eq)]
+enum Report {
+    Skipped(PathBuf),
+    Answer(String),
+    Failed(String),
+}
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Report::Skipped(input) => write!(f, "skipped, no input at {}", input.display()),
+            // Lined up under the first line, after the "dNN: " of the day.
+            Report::Answer(answer) => write!(f, "{}", answer.replace('\n', "\n     ")),
+            Report::Failed(error) => write!(f, "failed, {error}"),
+        }
+    }
+}
+
+/// Solves `day` with `solve` on its full input, or its example with `-e`,
+/// with the settings given by `params`.
+fn run_day(day: u32, solve: Solve, params: &Params) -> Report {
+    let report = aoc::run_day(day, params, |input| {
+        if let Some(error) = input.open_error().filter(|error| error.is_missing()) {
+            return Ok(Report::Skipped(error.path.clone()));
+        }
+
+        // A day that panics fails alone, the panic hook having printed why.
+        match panic::catch_unwind(AssertUnwindSafe(|| solve(input, params))) {
+            Ok(answer) => answer.map(Report::Answer),
+            Err(_) => Ok(Report::Failed("panicked".to_string())),
+        }
+    });
+    report.unwrap_or_else(|error| Report::Failed(error.to_string()))
+}
+
+fn main() -> ExitCode {
+    let params = match aoc::params() {
+        Ok(params) => params,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if params.help() {
+        println!("Usage: all [-e]\n\nRuns every day on its full input, or its example with -e.");
+        return ExitCode::SUCCESS;
+    }
+
+    let mut failed = false;
+    for (day, solve) in DAYS {
+        let report = run_day(day, solve, &params);
+        failed |= matches!(report, Report::Failed(_));
+        println!("d{day:02}: {report}");
+    }
+
+    match failed {
+        true => ExitCode::FAILURE,
+        false => ExitCode::SUCCESS,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::{days::d01, input};
+
+    use super::*;
+
+    #[test]
+    fn reports() {
+        let example = Params::from_args(["-e".to_string()]);
+        assert_eq!(
+            run_day(1, d01::solve, &example),
+            Report::Answer("Part 1: 24000\nPart 2: 45000".to_string())
+        );
+        assert_eq!(
+            run_day(1, d01::solve, &example).to_string(),
+            "Part 1: 24000\n     Part 2: 45000"
+        );
+
+        let missing = Params::from_args(["-e9".to_string()]);
+        assert_eq!(
+            run_day(3, DAYS[2].1, &missing),
+            Report::Skipped("input/examples/d03_9".into())
+        );
+        assert_eq!(
+            run_day(3, DAYS[2].1, &missing).to_string(),
+            "skipped, no input at input/examples/d03_9"
+        );
+
+        // Day 1 on the example of day 2.
+        let wrong_input = |_, params: &Params| d01::solve(input::input(2, true), params);
+        let report = run_day(1, wrong_input, &example);
+        assert!(
+            matches!(&report, Report::Failed(error) if error.contains("Invalid numerical input")),
+            "{report}"
+        );
+
+        let panicking = |_, _: &Params| panic!("on purpose");
+        assert_eq!(
+            run_day(1, panicking, &example),
+            Report::Failed("panicked".to_string())
+        );
+    }
+
+    #[test]
+    fn every_day() {
+        let days = DAYS.map(|(day, _)| day);
+        assert_eq!(days.to_vec(), (1..=15).collect::<Vec<_>>());
+
+        let example = Params::from_args(["-e".to_string()]);
+        for (day, solve) in DAYS {
+            let report = run_day(day, solve, &example);
+            assert!(matches!(report, Report::Answer(_)), "d{day:02}: {report}");
+        }
+    }
+}
@@ -0,0 +1,40 @@
+pub mod d01;
+pub mod d02;
+pub mod d03;
+pub mod d04;
+pub mod d05;
+pub mod d06;
+pub mod d07;
+pub mod d08;
+pub mod d09;
+pub mod d10;
+pub mod d11;
+pub mod d12;
+pub mod d13;
+pub mod d14;
+pub mod d15;
+
+use crate::{input::InputImpl, Params, Result};
+
+/// Solves a day on `input` with the settings in `params`, giving its answer
+/// as the binary of the day prints it.
+pub type Solve = fn(InputImpl, &Params) -> Result<String>;
+
+/// Every day in order, to run them all in one process.
+pub const DAYS: [(u32, Solve); 15] = [
+    (d01::DAY, d01::solve),
+    (d02::DAY, d02::solve),
+    (d03::DAY, d03::solve),
+    (d04::DAY, d04::solve),
+    (d05::DAY, d05::solve),
+    (d06::DAY, d06::solve),
+    (d07::DAY, d07::solve),
+    (d08::DAY, d08::solve),
+    (d09::DAY, d09::solve),
+    (d10::DAY, d10::solve),
+    (d11::DAY, d11::solve),
+    (d12::DAY, d12::solve),
+    (d13::DAY, d13::solve),
+    (d14::DAY, d14::solve),
+    (d15::DAY, d15::solve),
+];
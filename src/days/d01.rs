@@ -0,0 +1,108 @@
+use crate::{
+    input::{Input, InputError},
+    Answer,
+};
+use std::num::ParseIntError;
+
+pub const DAY: u32 = 1;
+
+/// Ordered by the calories carried.
+#[derive(Default, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Elf {
+    total: i64,
+}
+impl Elf {
+    fn input<I: Input>(input: I) -> impl Iterator<Item = ParseResult<Self>> {
+        input.blocks().map(|block| {
+            let mut elf = Elf::default();
+            for line in block? {
+                elf.line(line)?;
+            }
+            Ok(elf)
+        })
+    }
+
+    fn line(&mut self, line: String) -> ParseResult<()> {
+        let calories: i64 = line.parse()?;
+        self.total += calories;
+
+        Ok(())
+    }
+}
+
+fn answer<I: Input>(input: I) -> crate::Result<Answer<i64>> {
+    let elves = Elf::input(input);
+    let best_three = itertools::process_results(elves, |elves| crate::top_n(elves, 3))?;
+
+    Ok(Answer {
+        part1: best_three.first().ok_or(ParseError::EmptyList)?.total,
+        part2: best_three.iter().map(|elf| elf.total).sum::<i64>(),
+    })
+}
+
+pub fn main() -> crate::Exit {
+    crate::main_impl(DAY, answer).into()
+}
+
+pub fn solve(input: crate::input::InputImpl, _params: &crate::Params) -> crate::Result<String> {
+    Ok(answer(input)?.to_string())
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ParseError {
+    #[error("{0}")]
+    Input(#[from] InputError),
+    #[error("Invalid numerical input: {0}")]
+    ParseIntError(#[from] ParseIntError),
+    #[error("No elves in the input file")]
+    EmptyList,
+}
+impl From<ParseError> for crate::Error {
+    fn from(value: ParseError) -> Self {
+        crate::Error::Parsing(value.into())
+    }
+}
+type ParseResult<T> = Result<T, ParseError>;
+
+#[test]
+fn d01_example() {
+    assert_eq!(
+        answer(crate::input(DAY, true)).unwrap(),
+        Answer {
+            part1: 24000,
+            part2: 45000,
+        }
+    );
+}
+
+#[test]
+fn negative_calories() {
+    let lines = ["1000", "2000", "", "-500", "", "4000", "", "-200", "100"];
+    assert_eq!(
+        answer(crate::input::input_from_lines(&lines)).unwrap(),
+        Answer {
+            part1: 4000,
+            part2: 6900,
+        }
+    );
+
+    let hungry = ["-500", "", "-100"];
+    assert_eq!(
+        answer(crate::input::input_from_lines(&hungry)).unwrap(),
+        Answer {
+            part1: -100,
+            part2: -600,
+        }
+    );
+}
+
+#[test]
+fn elf_order() {
+    let elf = |total| Elf { total };
+    assert!(elf(24000) > elf(11000));
+    assert_eq!(elf(4000), elf(4000));
+    assert_eq!(
+        [elf(6000), elf(24000), elf(4000)].into_iter().max(),
+        Some(elf(24000))
+    );
+}
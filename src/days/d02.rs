@@ -0,0 +1,420 @@
+use crate::{input::Input, Answer};
+use std::str::FromStr;
+
+pub const DAY: u32 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Hand {
+    Rock,
+    Paper,
+    Scissors,
+}
+impl Hand {
+    fn score(self) -> u32 {
+        match self {
+            Hand::Rock => 1,
+            Hand::Paper => 2,
+            Hand::Scissors => 3,
+        }
+    }
+
+    /// The hand of its initial, `'R'`, `'P'` or `'S'`.
+    pub fn from_char(c: char) -> Option<Hand> {
+        match c {
+            'R' => Some(Hand::Rock),
+            'P' => Some(Hand::Paper),
+            'S' => Some(Hand::Scissors),
+            _ => None,
+        }
+    }
+
+    /// The hand winning against `opponent`.
+    pub fn best_response(opponent: Hand) -> Hand {
+        match opponent {
+            Hand::Rock => Hand::Paper,
+            Hand::Paper => Hand::Scissors,
+            Hand::Scissors => Hand::Rock,
+        }
+    }
+
+    /// The hand losing against `opponent`.
+    pub fn worst_response(opponent: Hand) -> Hand {
+        match opponent {
+            Hand::Rock => Hand::Scissors,
+            Hand::Paper => Hand::Rock,
+            Hand::Scissors => Hand::Paper,
+        }
+    }
+
+    fn opposing_for(self, outcome: GameOutcome) -> Hand {
+        match (self, outcome) {
+            (same, GameOutcome::Draw) => same,
+            (Hand::Rock, GameOutcome::Lose) => Hand::Paper,
+            (Hand::Rock, GameOutcome::Win) => Hand::Scissors,
+            (Hand::Paper, GameOutcome::Lose) => Hand::Scissors,
+            (Hand::Paper, GameOutcome::Win) => Hand::Rock,
+            (Hand::Scissors, GameOutcome::Lose) => Hand::Rock,
+            (Hand::Scissors, GameOutcome::Win) => Hand::Paper,
+        }
+    }
+
+    fn play(self, rhs: Hand) -> GameOutcome {
+        match (self, rhs) {
+            (Hand::Rock, Hand::Rock) => GameOutcome::Draw,
+            (Hand::Rock, Hand::Paper) => GameOutcome::Lose,
+            (Hand::Rock, Hand::Scissors) => GameOutcome::Win,
+            (Hand::Paper, Hand::Rock) => GameOutcome::Win,
+            (Hand::Paper, Hand::Paper) => GameOutcome::Draw,
+            (Hand::Paper, Hand::Scissors) => GameOutcome::Lose,
+            (Hand::Scissors, Hand::Rock) => GameOutcome::Lose,
+            (Hand::Scissors, Hand::Paper) => GameOutcome::Win,
+            (Hand::Scissors, Hand::Scissors) => GameOutcome::Draw,
+        }
+    }
+}
+impl std::fmt::Display for Hand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Hand::Rock => "Rock",
+            Hand::Paper => "Paper",
+            Hand::Scissors => "Scissors",
+        })
+    }
+}
+impl FromStr for Hand {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "A" => Hand::Rock,
+            "B" => Hand::Paper,
+            "C" => Hand::Scissors,
+            "X" => Hand::Rock,
+            "Y" => Hand::Paper,
+            "Z" => Hand::Scissors,
+            unknown => return Err(ParseError::UnknownHand(unknown.to_string())),
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GameOutcome {
+    Lose,
+    Draw,
+    Win,
+}
+impl GameOutcome {
+    fn inverse(self) -> GameOutcome {
+        match self {
+            GameOutcome::Lose => GameOutcome::Win,
+            GameOutcome::Draw => GameOutcome::Draw,
+            GameOutcome::Win => GameOutcome::Lose,
+        }
+    }
+
+    fn score(self) -> u32 {
+        match self {
+            GameOutcome::Win => 6,
+            GameOutcome::Draw => 3,
+            GameOutcome::Lose => 0,
+        }
+    }
+}
+impl std::fmt::Display for GameOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            GameOutcome::Win => "Win",
+            GameOutcome::Lose => "Lose",
+            GameOutcome::Draw => "Draw",
+        })
+    }
+}
+impl FromStr for GameOutcome {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "X" => GameOutcome::Lose,
+            "Y" => GameOutcome::Draw,
+            "Z" => GameOutcome::Win,
+            unknown => return Err(ParseError::UnknownOutcome(unknown.to_string())),
+        })
+    }
+}
+
+/// Hands of Rock Paper Scissors Lizard Spock.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ExtendedHand {
+    Rock,
+    Paper,
+    Scissors,
+    Lizard,
+    Spock,
+}
+impl ExtendedHand {
+    const ALL: [ExtendedHand; 5] = [
+        ExtendedHand::Rock,
+        ExtendedHand::Paper,
+        ExtendedHand::Scissors,
+        ExtendedHand::Lizard,
+        ExtendedHand::Spock,
+    ];
+
+    /// The hand of the opponent, from `A` to `E`, or of the player, from `V`
+    /// to `Z`, in the order of [`ExtendedHand::ALL`].
+    fn parse(s: &str, first: u8) -> Result<Self, ParseError> {
+        let unknown = || ParseError::UnknownExtendedHand(s.to_string());
+        let &[letter] = s.as_bytes() else {
+            return Err(unknown());
+        };
+        let index = letter.checked_sub(first).ok_or_else(unknown)?;
+        Self::ALL.get(index as usize).copied().ok_or_else(unknown)
+    }
+
+    pub fn score(self) -> u32 {
+        match self {
+            ExtendedHand::Rock => 1,
+            ExtendedHand::Paper => 2,
+            ExtendedHand::Scissors => 3,
+            ExtendedHand::Lizard => 4,
+            ExtendedHand::Spock => 5,
+        }
+    }
+
+    fn beats(self, rhs: ExtendedHand) -> bool {
+        use ExtendedHand::*;
+        matches!(
+            (self, rhs),
+            (Scissors, Paper)
+                | (Paper, Rock)
+                | (Rock, Lizard)
+                | (Lizard, Spock)
+                | (Spock, Scissors)
+                | (Scissors, Lizard)
+                | (Lizard, Paper)
+                | (Paper, Spock)
+                | (Spock, Rock)
+                | (Rock, Scissors)
+        )
+    }
+
+    pub fn play(self, rhs: ExtendedHand) -> GameOutcome {
+        match (self.beats(rhs), rhs.beats(self)) {
+            (true, _) => GameOutcome::Win,
+            (_, true) => GameOutcome::Lose,
+            _ => GameOutcome::Draw,
+        }
+    }
+
+    /// Score of the player playing `self` against `opponent`.
+    fn score_against(self, opponent: ExtendedHand) -> u32 {
+        self.score() + self.play(opponent).score()
+    }
+}
+
+/// A round of Rock Paper Scissors Lizard Spock, as `A V`.
+pub struct ExtendedMatch {
+    opponent: ExtendedHand,
+    player: ExtendedHand,
+}
+impl FromStr for ExtendedMatch {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (opponent, player) = s
+            .split_once(' ')
+            .ok_or_else(|| ParseError::BadLine(s.to_string()))?;
+
+        Ok(ExtendedMatch {
+            opponent: ExtendedHand::parse(opponent, b'A')?,
+            player: ExtendedHand::parse(player, b'V')?,
+        })
+    }
+}
+
+pub struct Match {
+    opponent: Hand,
+    player: Hand,
+    desired: GameOutcome,
+}
+impl Match {
+    fn score(&self) -> u32 {
+        self.player.score() + self.player.play(self.opponent).score()
+    }
+
+    fn desired_score(&self) -> u32 {
+        let player = self.opponent.opposing_for(self.desired.inverse());
+        player.score() + player.play(self.opponent).score()
+    }
+}
+impl FromStr for Match {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (opponent, player) = s
+            .split_once(' ')
+            .ok_or_else(|| ParseError::BadLine(s.to_string()))?;
+
+        Ok(Match {
+            opponent: opponent.parse()?,
+            player: player.parse()?,
+            desired: player.parse()?,
+        })
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ParseError {
+    #[error("Malformed input line {0:?}")]
+    BadLine(String),
+    #[error("{0:?} is not a valid hand, should be ABC or XYZ")]
+    UnknownHand(String),
+    #[error("{0:?} is not a valid outcome, should be XYZ")]
+    UnknownOutcome(String),
+    #[error("{0:?} is not a valid hand, should be ABCDE or VWXYZ")]
+    UnknownExtendedHand(String),
+}
+impl From<ParseError> for crate::Error {
+    fn from(value: ParseError) -> Self {
+        crate::Error::Parsing(value.into())
+    }
+}
+
+fn answer<I: Input>(input: I) -> crate::Result<Answer> {
+    let matches = input.parsed::<Match>();
+
+    let mut total_score = 0;
+    let mut total_desired = 0;
+    for a_match in matches {
+        let a_match = a_match?;
+        total_score += a_match.score();
+        total_desired += a_match.desired_score();
+    }
+
+    Ok(Answer {
+        part1: total_score,
+        part2: total_desired,
+    })
+}
+
+/// Scores of Rock Paper Scissors Lizard Spock: playing the hands given, and
+/// playing the best hand against each opponent.
+fn answer_extended<I: Input>(input: I) -> crate::Result<Answer> {
+    let mut total_score = 0;
+    let mut total_best = 0;
+    for a_match in input.parsed::<ExtendedMatch>() {
+        let ExtendedMatch { opponent, player } = a_match?;
+        total_score += player.score_against(opponent);
+        total_best += ExtendedHand::ALL
+            .iter()
+            .map(|hand| hand.score_against(opponent))
+            .max()
+            .unwrap_or_default();
+    }
+
+    Ok(Answer {
+        part1: total_score,
+        part2: total_best,
+    })
+}
+
+pub fn main() -> crate::Exit {
+    run().into()
+}
+
+pub fn solve(input: crate::input::InputImpl, params: &crate::Params) -> crate::Result<String> {
+    let answer = match params.flag("extended") {
+        true => answer_extended(input)?,
+        false => answer(input)?,
+    };
+    Ok(answer.to_string())
+}
+
+/// Plays Rock Paper Scissors Lizard Spock with `--extended`.
+fn run() -> crate::Result<()> {
+    match crate::params()?.flag("extended") {
+        true => crate::main_impl(DAY, answer_extended),
+        false => crate::main_impl(DAY, answer),
+    }
+}
+
+#[test]
+pub fn d02_example() {
+    assert_eq!(
+        answer(crate::input(DAY, true)).unwrap(),
+        Answer {
+            part1: 15,
+            part2: 12,
+        }
+    )
+}
+
+#[test]
+fn display() {
+    let hand = |c| Hand::from_char(c).unwrap();
+    assert_eq!(hand('R').to_string(), "Rock");
+    assert_eq!(hand('P').to_string(), "Paper");
+    assert_eq!(hand('S').to_string(), "Scissors");
+    assert_eq!(Hand::from_char('X'), None);
+
+    assert_eq!(hand('P').play(hand('R')).to_string(), "Win");
+    assert_eq!(hand('P').play(hand('S')).to_string(), "Lose");
+    assert_eq!(hand('S').play(hand('S')).to_string(), "Draw");
+}
+
+#[test]
+fn line_numbers() {
+    let error = answer(crate::input::input_from_lines(&["A Y", "B Q"])).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "line 2: \"Q\" is not a valid hand, should be ABC or XYZ"
+    );
+}
+
+#[test]
+fn responses() {
+    let hands = [Hand::Rock, Hand::Paper, Hand::Scissors];
+    for (opponent, player) in hands.into_iter().flat_map(|a| hands.map(|b| (a, b))) {
+        let outcome = player.play(opponent);
+        assert_eq!(
+            player == Hand::best_response(opponent),
+            outcome == GameOutcome::Win
+        );
+        assert_eq!(
+            player == Hand::worst_response(opponent),
+            outcome == GameOutcome::Lose
+        );
+    }
+}
+
+#[test]
+fn extended() {
+    use ExtendedHand::*;
+    for hand in ExtendedHand::ALL {
+        assert_eq!(hand.play(hand), GameOutcome::Draw);
+        let wins = ExtendedHand::ALL
+            .iter()
+            .filter(|&&rhs| hand.play(rhs) == GameOutcome::Win);
+        assert_eq!(wins.count(), 2, "{hand:?}");
+    }
+    assert_eq!(Spock.play(Rock), GameOutcome::Win);
+    assert_eq!(Rock.play(Spock), GameOutcome::Lose);
+    assert_eq!(Lizard.play(Paper), GameOutcome::Win);
+
+    // All three lose. The best hands win: Lizard against Paper (4 + 6),
+    // Scissors against Lizard (3 + 6) and Spock against Scissors (5 + 6).
+    let lines = ["B V", "D Z", "C Y"];
+    assert_eq!(
+        answer_extended(crate::input::input_from_lines(&lines)).unwrap(),
+        Answer {
+            part1: 1 + 5 + 4,
+            part2: 10 + 9 + 11,
+        }
+    );
+
+    let error = answer_extended(crate::input::input_from_lines(&["A X", "F V"])).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "line 2: \"F\" is not a valid hand, should be ABCDE or VWXYZ"
+    );
+}
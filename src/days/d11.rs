@@ -0,0 +1,2023 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap, VecDeque},
+    fs::File,
+    io,
+    iter::{Enumerate, Peekable},
+    num::ParseIntError,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::{
+    input::{Input, InputError},
+    params::ParamError,
+    Answer,
+};
+use itertools::Itertools;
+
+pub const DAY: u32 = 11;
+
+#[derive(Debug, Clone)]
+struct Monkey<T: Item = SimpleItem> {
+    items: VecDeque<T>,
+    operation: Operation,
+    test: Test,
+}
+impl Monkey<SimpleItem> {
+    const STARTING: &'static str = "Starting items";
+    const OPERATION: &'static str = "Operation";
+    const TEST: &'static str = "Test";
+
+    /// Builds the `monkey`-th (zero-based) monkey, locating any error at the
+    /// line of the offending attribute.
+    fn from_tree_checked(
+        tree: &Tree,
+        monkey: usize,
+        config: &Config,
+    ) -> Result<Self, LocatedMonkeyError> {
+        let located = |(line, error)| LocatedMonkeyError {
+            monkey,
+            line,
+            error,
+        };
+
+        let mut unknown = tree.unknown_attributes(&[Self::STARTING, Self::OPERATION, Self::TEST]);
+        if let Ok(TreeValue::Subtree(_, test)) = tree.get(Self::TEST) {
+            unknown.extend(
+                test.unknown_attributes(&[Test::IF_TRUE, Test::IF_FALSE])
+                    .into_iter()
+                    .map(|key| format!("{} > {key}", Self::TEST)),
+            );
+        }
+
+        let parsed = Self::from_tree(tree);
+        if unknown.is_empty() {
+            return parsed.map_err(located);
+        }
+
+        if config.strict {
+            return Err(located((
+                tree.line,
+                MonkeyError::UnknownAttributes(unknown, parsed.err().map(|e| Box::new(e.1))),
+            )));
+        }
+
+        if config.verbose {
+            eprintln!("Ignoring unknown attributes {unknown:?} of monkey {monkey}");
+        }
+
+        parsed.map_err(located)
+    }
+
+    fn from_tree(tree: &Tree) -> Located<Self, MonkeyError> {
+        let items = tree.with(Self::STARTING, |items| {
+            let items = items.get_value()?;
+            items
+                .split(',')
+                .map(|x| x.trim())
+                .map(|x| Ok(SimpleItem(x.parse()?)))
+                .collect::<Result<_, _>>()
+                .map_err(|e| MonkeyError::InvalidItem(items.to_string(), e))
+        })?;
+
+        let operation = tree.with(Self::OPERATION, |operation| {
+            MonkeyResult::Ok(operation.get_value()?.parse()?)
+        })?;
+
+        let (main, test) = tree.with(Self::TEST, TreeValue::get_subtree)?;
+        let test = Test::from_tree(main, test).map_err(|(line, e)| (line, e.into()))?;
+
+        Ok(Monkey {
+            items,
+            operation,
+            test,
+        })
+    }
+}
+
+impl<T: Item> Monkey<T> {
+    fn targets(&self) -> [usize; 2] {
+        [self.test.if_true, self.test.if_false]
+    }
+
+    fn inspect(&mut self, relief: Relief) -> Option<(usize, T)> {
+        let mut item = self.items.pop_front()?;
+        item.operate(self.operation);
+        item.relax(relief);
+
+        let to = self.test.test(&item);
+
+        Some((to, item))
+    }
+
+    fn receive(&mut self, item: T) {
+        self.items.push_back(item);
+    }
+
+    fn map<U: Item, F: FnMut(&T) -> U>(&self, f: F) -> Monkey<U> {
+        let items = self.items.iter().map(f).collect();
+
+        Monkey {
+            items,
+            operation: self.operation,
+            test: self.test,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Monkeys<T: Item = SimpleItem>(Vec<Monkey<T>>);
+impl<T: Item> Monkeys<T> {
+    fn new(monkeys: Vec<Monkey<T>>) -> Result<Self, MonkeysError> {
+        for (idx, monkey) in monkeys.iter().enumerate() {
+            for target in monkey.targets() {
+                if target == idx {
+                    return Err(MonkeysError::SelfThrow(idx));
+                }
+                if target >= monkeys.len() {
+                    return Err(MonkeysError::InvalidTarget {
+                        monkey: idx,
+                        target,
+                        count: monkeys.len(),
+                    });
+                }
+            }
+        }
+
+        Ok(Monkeys(monkeys))
+    }
+
+    fn map<U: Item, F: FnMut(&T) -> U>(&self, mut f: F) -> Monkeys<U> {
+        Monkeys(self.0.iter().map(|monkey| monkey.map(&mut f)).collect())
+    }
+
+    /// Numbers every item in the order it was listed in the input.
+    fn tracked(&self) -> Monkeys<Tracked<T>>
+    where
+        T: Clone,
+    {
+        let mut id = 0;
+        self.map(|item| {
+            id += 1;
+            Tracked {
+                id: id - 1,
+                inner: item.clone(),
+            }
+        })
+    }
+}
+impl Monkeys<SimpleItem> {
+    fn modular(&self) -> Monkeys<ModuleItem> {
+        let modules = self.0.iter().map(|x| x.test.divisible);
+
+        self.map(|item| ModuleItem::new(item.0, modules.clone()))
+    }
+
+    /// Runs independent simulations of these monkeys, on worker threads when
+    /// the `rayon` feature is enabled.
+    fn run_many(&self, configs: &[SimConfig]) -> Vec<SimResult> {
+        let simple = Simulator::new(self.clone(), Relief::None).unwrap();
+        let modular = Simulator::new(self.modular(), Relief::None).unwrap();
+
+        let run = |config: &SimConfig| match config.relief {
+            Relief::DivideBy(_) => {
+                let mut simulator = simple.fork();
+                // Simple items take any relief, no need to check it again.
+                simulator.relief = config.relief;
+                simulator.run(config.rounds);
+                simulator.result()
+            }
+            Relief::None => {
+                let mut simulator = modular.fork();
+                simulator.run(config.rounds);
+                simulator.result()
+            }
+        };
+
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            configs.par_iter().map(run).collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            configs.iter().map(run).collect()
+        }
+    }
+
+    fn simulate(&self, run: SimConfig, config: &Config) -> crate::Result<u64> {
+        let SimConfig { rounds, relief } = run;
+
+        Ok(match (relief, config.verbose) {
+            (Relief::DivideBy(_), false) => {
+                Simulator::new(self.clone(), relief)?.simulate(rounds, config)?
+            }
+            (Relief::DivideBy(_), true) => {
+                Simulator::new(self.tracked(), relief)?.simulate(rounds, config)?
+            }
+            (Relief::None, false) => {
+                Simulator::new(self.modular(), relief)?.simulate(rounds, config)?
+            }
+            (Relief::None, true) => {
+                let modules = self.0.iter().map(|x| x.test.divisible).join("/");
+                eprintln!("(worry levels are shown as residues modulo {modules})\n");
+
+                Simulator::new(self.modular().tracked(), relief)?.simulate(rounds, config)?
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Simulator<T: Item> {
+    monkeys: Vec<Monkey<T>>,
+    activity: Vec<u64>,
+    relief: Relief,
+    rounds: usize,
+}
+impl<T: Item> Simulator<T> {
+    fn new(monkeys: Monkeys<T>, relief: Relief) -> Result<Self, ReliefError> {
+        T::check_relief(relief)?;
+
+        Ok(Simulator {
+            activity: vec![0; monkeys.0.len()],
+            monkeys: monkeys.0,
+            relief,
+            rounds: 0,
+        })
+    }
+
+    fn round(&mut self) {
+        self.round_with(&mut |_| {})
+    }
+
+    fn round_with<F: FnMut(ThrowEvent)>(&mut self, on_throw: &mut F) {
+        let round = self.rounds + 1;
+
+        for (from, count) in self.activity.iter_mut().enumerate() {
+            while let Some((to, item)) = self.monkeys[from].inspect(self.relief) {
+                *count += 1;
+                on_throw(ThrowEvent {
+                    round,
+                    from,
+                    to,
+                    item_index: item.id(),
+                    worry: item.worry(),
+                });
+                self.monkeys[to].receive(item);
+            }
+        }
+
+        self.rounds = round;
+    }
+
+    fn run_with<F: FnMut(ThrowEvent)>(&mut self, rounds: usize, mut on_throw: F) {
+        for _ in 0..rounds {
+            self.round_with(&mut on_throw);
+        }
+    }
+
+    fn run(&mut self, rounds: usize) {
+        for _ in 0..rounds {
+            self.round();
+        }
+    }
+
+    fn simulate(mut self, rounds: usize, config: &Config) -> Result<u64, BusinessError> {
+        if !config.verbose {
+            self.run(rounds);
+            return self.monkey_business(config.top);
+        }
+
+        let mut throws = BTreeMap::<usize, usize>::new();
+        let mut count = |event: ThrowEvent| {
+            if let Some(item) = event.item_index {
+                *throws.entry(item).or_default() += 1;
+            }
+        };
+        for _ in 0..rounds {
+            self.run_with(1, &mut count);
+            self.report();
+        }
+
+        if !throws.is_empty() {
+            let throws = throws
+                .into_iter()
+                .map(|(item, count)| format!("#{item} ({count})"))
+                .join(", ");
+            eprintln!("Throws per item: {throws}\n");
+        }
+
+        let busiest = self
+            .busiest(config.top)
+            .into_iter()
+            .map(|(idx, count)| format!("{idx} ({count})"))
+            .join(", ");
+        eprintln!("Busiest monkeys: {busiest}\n");
+
+        self.monkey_business(config.top)
+    }
+
+    fn busiest(&self, k: usize) -> Vec<(usize, u64)> {
+        busiest(&self.activity, k)
+    }
+
+    fn monkey_business(&self, k: usize) -> Result<u64, BusinessError> {
+        monkey_business(&self.activity, k)
+    }
+
+    /// A copy of this simulator's monkeys and items, with activity and the
+    /// round count starting over.
+    fn fork(&self) -> Self
+    where
+        T: Clone,
+    {
+        Simulator {
+            monkeys: self.monkeys.clone(),
+            activity: vec![0; self.activity.len()],
+            relief: self.relief,
+            rounds: 0,
+        }
+    }
+
+    fn result(&self) -> SimResult {
+        SimResult {
+            rounds: self.rounds,
+            activity: self.activity.clone(),
+        }
+    }
+
+    fn report(&self) {
+        if self.rounds <= 20 || self.rounds.is_multiple_of(1000) {
+            eprintln!("== After round {} ==", self.rounds);
+            eprintln!("{}", self.holdings());
+            eprintln!("{}\n", self.inspections());
+        }
+    }
+
+    fn holdings(&self) -> String {
+        self.monkeys
+            .iter()
+            .enumerate()
+            .map(|(idx, monkey)| format!("Monkey {idx}: {}", monkey.items.iter().join(", ")))
+            .join("\n")
+    }
+
+    fn inspections(&self) -> String {
+        self.activity
+            .iter()
+            .enumerate()
+            .map(|(idx, count)| format!("Monkey {idx} inspected items {count} times."))
+            .join("\n")
+    }
+}
+
+impl<T: Item + Checkpoint> Simulator<T> {
+    const CHECKPOINT_HEADER: &'static str = "d11 checkpoint v1";
+
+    fn save<W: io::Write>(&self, mut w: W) -> io::Result<()> {
+        writeln!(w, "{}", Self::CHECKPOINT_HEADER)?;
+        writeln!(w, "rounds: {}", self.rounds)?;
+        match self.relief {
+            Relief::DivideBy(by) => writeln!(w, "relief: divide by {by}")?,
+            Relief::None => writeln!(w, "relief: none")?,
+        }
+        writeln!(w, "monkeys: {}", self.monkeys.len())?;
+
+        for (monkey, activity) in self.monkeys.iter().zip(&self.activity) {
+            let Test {
+                divisible,
+                if_true,
+                if_false,
+            } = monkey.test;
+
+            writeln!(w, "operation: new = {}", monkey.operation)?;
+            writeln!(w, "test: {divisible} {if_true} {if_false}")?;
+            writeln!(w, "activity: {activity}")?;
+            writeln!(
+                w,
+                "items: {}",
+                monkey.items.iter().map(Checkpoint::encode).join(" ")
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn load<R: io::BufRead>(r: R) -> Result<Self, CheckpointError> {
+        let mut lines = r.lines().enumerate();
+        let mut next = |name: &'static str| -> Result<(usize, String), CheckpointError> {
+            let (idx, line) = lines.next().ok_or(CheckpointError::Truncated(name))?;
+            let line = line?;
+            let value = match name.is_empty() {
+                true => Some(line.as_str()),
+                false => line
+                    .strip_prefix(name)
+                    .and_then(|line| line.strip_prefix(": ")),
+            };
+
+            value
+                .map(|value| (idx + 1, value.to_string()))
+                .ok_or_else(|| CheckpointError::Format(idx + 1, name, line.clone()))
+        };
+        let number = |(line, value): (usize, String), name| {
+            value
+                .parse::<usize>()
+                .map_err(|_| CheckpointError::Format(line, name, value))
+        };
+
+        let (line, header) = next("")?;
+        if header != Self::CHECKPOINT_HEADER {
+            return Err(CheckpointError::Format(line, "header", header));
+        }
+
+        let rounds = number(next("rounds")?, "rounds")?;
+        let relief = match next("relief")? {
+            (_, relief) if relief == "none" => Relief::None,
+            (line, relief) => Relief::DivideBy(
+                relief
+                    .strip_prefix("divide by ")
+                    .and_then(|by| by.parse().ok())
+                    .filter(|&by| by > 0)
+                    .ok_or(CheckpointError::Format(line, "relief", relief))?,
+            ),
+        };
+        let count = number(next("monkeys")?, "monkeys")?;
+
+        // Not preallocated for `count`, which a corrupt file may make huge.
+        let mut monkeys = Vec::new();
+        let mut activity = Vec::new();
+        for _ in 0..count {
+            let (line, operation) = next("operation")?;
+            let operation = operation
+                .parse()
+                .map_err(|_| CheckpointError::Format(line, "operation", operation))?;
+
+            let (line, test) = next("test")?;
+            let test = test
+                .split(' ')
+                .map(|x| x.parse::<usize>().ok())
+                .collect::<Option<Vec<_>>>()
+                .and_then(|test| match test[..] {
+                    [divisible, if_true, if_false] => Some(Test {
+                        divisible: divisible.try_into().ok()?,
+                        if_true,
+                        if_false,
+                    }),
+                    _ => None,
+                })
+                .ok_or(CheckpointError::Format(line, "test", test))?;
+
+            activity.push(number(next("activity")?, "activity")? as u64);
+
+            let (line, items) = next("items")?;
+            let items = items
+                .split(' ')
+                .filter(|item| !item.is_empty())
+                .map(T::decode)
+                .collect::<Option<_>>()
+                .ok_or(CheckpointError::Format(line, "items", items))?;
+
+            monkeys.push(Monkey {
+                items,
+                operation,
+                test,
+            });
+        }
+
+        let divisors = monkeys
+            .iter()
+            .map(|monkey| monkey.test.divisible)
+            .collect::<Vec<_>>();
+        for (idx, monkey) in monkeys.iter().enumerate() {
+            if let Some(item) = monkey.items.iter().find(|item| !item.covers(&divisors)) {
+                return Err(CheckpointError::Moduli(idx, item.encode()));
+            }
+        }
+
+        let mut simulator = Simulator::new(Monkeys::new(monkeys)?, relief)?;
+        simulator.activity = activity;
+        simulator.rounds = rounds;
+
+        Ok(simulator)
+    }
+
+    /// Runs until `rounds` rounds have been simulated in total, saving a
+    /// checkpoint whenever the round count is a multiple of `every`.
+    fn run_checkpointed(
+        &mut self,
+        rounds: usize,
+        every: usize,
+        path: &Path,
+    ) -> Result<(), CheckpointError> {
+        while self.rounds < rounds {
+            self.round();
+
+            if self.rounds.is_multiple_of(every) {
+                self.save(io::BufWriter::new(File::create(path)?))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// How an item is written to and read back from a checkpoint.
+trait Checkpoint: Sized {
+    fn encode(&self) -> String;
+    fn decode(s: &str) -> Option<Self>;
+    /// Whether the item can be tested for divisibility by each of `divisors`.
+    fn covers(&self, _divisors: &[u32]) -> bool {
+        true
+    }
+}
+impl Checkpoint for SimpleItem {
+    fn encode(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn decode(s: &str) -> Option<Self> {
+        Some(SimpleItem(s.parse().ok()?))
+    }
+}
+impl Checkpoint for ModuleItem {
+    fn encode(&self) -> String {
+        self.values
+            .iter()
+            .map(|(value, modulo)| format!("{value}%{modulo}"))
+            .join("/")
+    }
+
+    fn decode(s: &str) -> Option<Self> {
+        let values = s
+            .split('/')
+            .map(|value| {
+                let (value, modulo) = value.split_once('%')?;
+                let (value, modulo) = (value.parse().ok()?, modulo.parse().ok()?);
+                (value < modulo).then_some((value, modulo))
+            })
+            .collect::<Option<_>>()?;
+
+        Some(ModuleItem { values })
+    }
+
+    fn covers(&self, divisors: &[u32]) -> bool {
+        divisors
+            .iter()
+            .all(|divisor| self.values.iter().any(|(_, modulo)| modulo == divisor))
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+enum CheckpointError {
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    #[error("Checkpoint ended before {0:?}")]
+    Truncated(&'static str),
+    #[error("Checkpoint line {0}: bad {1:?} entry {2:?}")]
+    Format(usize, &'static str, String),
+    #[error("{0}")]
+    Monkeys(#[from] MonkeysError),
+    #[error("{0}")]
+    Relief(#[from] ReliefError),
+    #[error("Checkpoint item {1:?} of monkey {0} is missing the modulus of a test")]
+    Moduli(usize, String),
+}
+impl From<CheckpointError> for crate::Error {
+    fn from(value: CheckpointError) -> Self {
+        crate::Error::Parsing(value.into())
+    }
+}
+
+/// The `k` most active monkeys as `(index, inspections)`, ties going to the
+/// lowest index.
+fn busiest(activity: &[u64], k: usize) -> Vec<(usize, u64)> {
+    let ranked = activity
+        .iter()
+        .enumerate()
+        .map(|(idx, &count)| (count, Reverse(idx)));
+    crate::top_n(ranked, k)
+        .into_iter()
+        .map(|(count, Reverse(idx))| (idx, count))
+        .collect()
+}
+
+fn monkey_business(activity: &[u64], k: usize) -> Result<u64, BusinessError> {
+    let business = busiest(activity, k)
+        .into_iter()
+        .try_fold(1u128, |product, (_, count)| {
+            product.checked_mul(count as u128)
+        })
+        .ok_or(BusinessError(k))?;
+
+    business.try_into().map_err(|_| BusinessError(k))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SimConfig {
+    rounds: usize,
+    relief: Relief,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SimResult {
+    rounds: usize,
+    activity: Vec<u64>,
+}
+impl SimResult {
+    fn monkey_business(&self, k: usize) -> Result<u64, BusinessError> {
+        monkey_business(&self.activity, k)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Relief {
+    DivideBy(u64),
+    None,
+}
+impl FromStr for Relief {
+    type Err = BadRelief;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Relief::None),
+            by => match by.parse() {
+                Ok(0) | Err(_) => Err(BadRelief(s.to_string())),
+                Ok(by) => Ok(Relief::DivideBy(by)),
+            },
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Bad relief {0:?}, expected none or a number to divide by")]
+struct BadRelief(String);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ThrowEvent {
+    round: usize,
+    from: usize,
+    to: usize,
+    /// Only known for [`Tracked`] items.
+    item_index: Option<usize>,
+    /// Only known for items holding their actual worry level.
+    worry: Option<u64>,
+}
+
+trait Item: std::fmt::Display {
+    fn check_relief(_relief: Relief) -> Result<(), ReliefError> {
+        Ok(())
+    }
+    fn relax(&mut self, relief: Relief);
+    fn divisible(&self, by: u32) -> bool;
+    fn operate(&mut self, operation: Operation);
+    fn worry(&self) -> Option<u64>;
+    fn id(&self) -> Option<usize> {
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Tracked<T> {
+    id: usize,
+    inner: T,
+}
+impl<T: Item> Item for Tracked<T> {
+    fn check_relief(relief: Relief) -> Result<(), ReliefError> {
+        T::check_relief(relief)
+    }
+
+    fn relax(&mut self, relief: Relief) {
+        self.inner.relax(relief)
+    }
+
+    fn divisible(&self, by: u32) -> bool {
+        self.inner.divisible(by)
+    }
+
+    fn operate(&mut self, operation: Operation) {
+        self.inner.operate(operation)
+    }
+
+    fn worry(&self) -> Option<u64> {
+        self.inner.worry()
+    }
+
+    fn id(&self) -> Option<usize> {
+        Some(self.id)
+    }
+}
+impl<T: std::fmt::Display> std::fmt::Display for Tracked<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SimpleItem(u64);
+impl Item for SimpleItem {
+    fn relax(&mut self, relief: Relief) {
+        if let Relief::DivideBy(by) = relief {
+            self.0 /= by;
+        }
+    }
+
+    fn divisible(&self, by: u32) -> bool {
+        self.0.is_multiple_of(by as u64)
+    }
+
+    fn operate(&mut self, operation: Operation) {
+        self.0 = match operation {
+            Operation::Square => self.0 * self.0,
+            Operation::Multiply(right) => self.0 * right as u64,
+            Operation::Sum(right) => self.0 + right as u64,
+        }
+    }
+
+    fn worry(&self) -> Option<u64> {
+        Some(self.0)
+    }
+}
+
+impl std::fmt::Display for SimpleItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Clone)]
+struct ModuleItem {
+    values: Vec<(u32, u32)>,
+}
+impl std::fmt::Debug for ModuleItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ModuleItem")
+            .field(&self.values /* .last().unwrap().0*/)
+            .finish()
+    }
+}
+impl std::fmt::Display for ModuleItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.values.iter().map(|(value, _)| value).join("/")
+        )
+    }
+}
+impl ModuleItem {
+    fn new<I: IntoIterator<Item = u32>>(base: u64, modulos: I) -> ModuleItem {
+        let values = modulos
+            .into_iter()
+            .map(|modulo| ((base % modulo as u64) as u32, modulo))
+            .collect();
+
+        ModuleItem { values }
+    }
+}
+impl Item for ModuleItem {
+    fn check_relief(relief: Relief) -> Result<(), ReliefError> {
+        match relief {
+            Relief::DivideBy(by) => Err(ReliefError(by)),
+            Relief::None => Ok(()),
+        }
+    }
+
+    fn relax(&mut self, relief: Relief) {
+        debug_assert_eq!(relief, Relief::None);
+    }
+
+    fn divisible(&self, by: u32) -> bool {
+        self.values
+            .iter()
+            .find(|(_, modulo)| *modulo == by)
+            .unwrap()
+            .0
+            == 0
+    }
+
+    fn operate(&mut self, operation: Operation) {
+        match operation {
+            Operation::Square => {
+                for (value, modulo) in self.values.iter_mut() {
+                    *value = ((*value) * (*value)) % *modulo;
+                }
+            }
+            Operation::Multiply(right) => {
+                for (value, modulo) in self.values.iter_mut() {
+                    *value = ((*value) * (right % *modulo)) % *modulo;
+                }
+            }
+            Operation::Sum(right) => {
+                for (value, modulo) in self.values.iter_mut() {
+                    *value = ((*value) + (right % *modulo)) % *modulo;
+                }
+            }
+        }
+    }
+
+    fn worry(&self) -> Option<u64> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Operation {
+    Square,
+    Multiply(u32),
+    Sum(u32),
+}
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operation::Square => write!(f, "old * old"),
+            Operation::Multiply(right) => write!(f, "old * {right}"),
+            Operation::Sum(right) => write!(f, "old + {right}"),
+        }
+    }
+}
+impl FromStr for Operation {
+    type Err = OperationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s
+            .strip_prefix("new = ")
+            .ok_or_else(|| OperationError::InvalidPrefix(s.to_string()))?;
+
+        let mut comps = s.splitn(3, ' ');
+        let left = comps.next().unwrap_or_default();
+        let operator = comps.next().unwrap_or_default();
+        let right = comps.next().unwrap_or_default();
+
+        let left: Operand = left.parse()?;
+        let right: Operand = right.parse()?;
+
+        Ok(match (left, operator, right) {
+            (Operand::Old, "+", Operand::Number(right)) => Operation::Sum(right),
+            (Operand::Old, "*", Operand::Number(right)) => Operation::Multiply(right),
+            (Operand::Old, "*", Operand::Old) => Operation::Square,
+            _ => return Err(OperationError::InvalidOperation(s.to_string())),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Operand {
+    Number(u32),
+    Old,
+}
+impl FromStr for Operand {
+    type Err = OperationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "old" {
+            return Ok(Operand::Old);
+        }
+
+        Ok(Operand::Number(s.parse()?))
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Test {
+    divisible: u32,
+    if_true: usize,
+    if_false: usize,
+}
+impl Test {
+    const IF_TRUE: &'static str = "If true";
+    const IF_FALSE: &'static str = "If false";
+
+    fn from_tree(main: &str, tree: &Tree) -> Located<Self, TestError> {
+        const DIVISIBLE: &str = "divisible by ";
+        const THROW: &str = "throw to monkey ";
+
+        let divisible = main
+            .strip_prefix(DIVISIBLE)
+            .ok_or_else(|| TestError::InvalidPrefix(DIVISIBLE, main.to_string()))
+            .and_then(|divisible| Ok(divisible.parse()?))
+            .map_err(|e| (tree.line, e))?;
+
+        let target = |target: &TreeValue| -> Result<usize, TestError> {
+            let target = target.get_value()?;
+            let target = target
+                .strip_prefix(THROW)
+                .ok_or_else(|| TestError::InvalidPrefix(THROW, target.to_string()))?
+                .parse()?;
+
+            Ok(target)
+        };
+
+        let if_true = tree.with(Self::IF_TRUE, target)?;
+        let if_false = tree.with(Self::IF_FALSE, target)?;
+
+        Ok(Test {
+            divisible,
+            if_true,
+            if_false,
+        })
+    }
+
+    fn test<T: Item>(&self, item: &T) -> usize {
+        match item.divisible(self.divisible) {
+            true => self.if_true,
+            false => self.if_false,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+enum MonkeyError {
+    #[error("{0}")]
+    MissingAttribute(#[from] MissingAttribute),
+    #[error("{0}")]
+    ExtraneousSubtree(#[from] ExtraneousSubtree),
+    #[error("{0}")]
+    MissingSubtree(#[from] MissingSubtree),
+    #[error("{1}, invalid number on item list {0:?}")]
+    InvalidItem(String, ParseIntError),
+    #[error("{0}")]
+    Operation(#[from] OperationError),
+    #[error("{0}")]
+    TestError(#[from] TestError),
+    #[error(
+        "Unknown attributes {0:?}{}",
+        .1.as_ref().map(|e| format!(", and {e}")).unwrap_or_default()
+    )]
+    UnknownAttributes(Vec<String>, Option<Box<MonkeyError>>),
+}
+impl From<MonkeyError> for crate::Error {
+    fn from(value: MonkeyError) -> Self {
+        crate::Error::Semantic(value.into())
+    }
+}
+type MonkeyResult<T> = Result<T, MonkeyError>;
+
+/// An error paired with the line it happened on.
+type Located<T, E> = Result<T, (usize, E)>;
+
+#[derive(thiserror::Error, Debug)]
+#[error("monkey {monkey} (line {line}): {error}")]
+struct LocatedMonkeyError {
+    monkey: usize,
+    line: usize,
+    error: MonkeyError,
+}
+impl From<LocatedMonkeyError> for crate::Error {
+    fn from(value: LocatedMonkeyError) -> Self {
+        crate::Error::Semantic(value.into())
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+enum MonkeysError {
+    #[error("Monkey {monkey} throws to monkey {target}, but there are only {count} monkeys")]
+    InvalidTarget {
+        monkey: usize,
+        target: usize,
+        count: usize,
+    },
+    #[error("Monkey {0} throws to itself")]
+    SelfThrow(usize),
+}
+impl From<MonkeysError> for crate::Error {
+    fn from(value: MonkeysError) -> Self {
+        crate::Error::Semantic(value.into())
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Dividing worry levels by {0} can't be expressed on modular items")]
+struct ReliefError(u64);
+impl From<ReliefError> for crate::Error {
+    fn from(value: ReliefError) -> Self {
+        crate::Error::Semantic(value.into())
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Monkey business of the {0} busiest monkeys doesn't fit in 64 bits")]
+struct BusinessError(usize);
+impl From<BusinessError> for crate::Error {
+    fn from(value: BusinessError) -> Self {
+        crate::Error::Semantic(value.into())
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+enum OperationError {
+    #[error("Operation must start with {pre:?}, got {0:?}", pre = "new = ")]
+    InvalidPrefix(String),
+    #[error("{0}")]
+    ParseIntError(#[from] ParseIntError),
+    #[error("Invalid operation: {0:?}")]
+    InvalidOperation(String),
+}
+
+#[derive(thiserror::Error, Debug)]
+enum TestError {
+    #[error("Invalid prefix {1:?}, expected {0:?}")]
+    InvalidPrefix(&'static str, String),
+    #[error("{0}")]
+    MissingAttribute(#[from] MissingAttribute),
+    #[error("{0}")]
+    ParseIntError(#[from] ParseIntError),
+    #[error("{0}")]
+    ExtraneousSubtree(#[from] ExtraneousSubtree),
+}
+
+#[derive(Debug)]
+struct Tree {
+    line: usize,
+    attributes: HashMap<String, Attribute>,
+}
+impl Tree {
+    fn input<I: Input>(input: I) -> impl Iterator<Item = ParseResult<(String, Tree)>> {
+        let mut reader = TreeReader {
+            input: input.enumerate().peekable(),
+            indentation: None,
+        };
+
+        std::iter::from_fn(move || {
+            let (line, name) = loop {
+                match reader.input.next()? {
+                    (_, Ok(name)) if name.is_empty() => continue,
+                    (idx, Ok(name)) => break (idx + 1, name),
+                    (_, Err(e)) => return Some(Err(e.into())),
+                }
+            };
+
+            let name = match name.split_once(':') {
+                Some((name, _)) => name,
+                None => return Some(Err(ParseError::MissingSeparator(line, name.to_string()))),
+            };
+
+            reader
+                .next_tree(line, 1)
+                .transpose()
+                .map(|r| r.map(|tree| (name.to_string(), tree)))
+        })
+    }
+
+    fn get(&self, attribute: &'static str) -> Result<&TreeValue, MissingAttribute> {
+        self.attributes
+            .get(attribute)
+            .map(|attribute| &attribute.value)
+            .ok_or(MissingAttribute(attribute))
+    }
+
+    /// Applies `f` to `attribute`, locating a missing attribute at the line
+    /// of this tree and any error from `f` at the line of the attribute.
+    fn with<'a, T, E, M, F>(&'a self, attribute: &'static str, f: F) -> Located<T, M>
+    where
+        F: FnOnce(&'a TreeValue) -> Result<T, E>,
+        M: From<MissingAttribute> + From<E>,
+    {
+        let found = self
+            .attributes
+            .get(attribute)
+            .ok_or_else(|| (self.line, MissingAttribute(attribute).into()))?;
+
+        f(&found.value).map_err(|e| (found.line, e.into()))
+    }
+
+    fn unknown_attributes(&self, known: &[&str]) -> Vec<String> {
+        self.attributes
+            .keys()
+            .filter(|key| !known.contains(&key.as_str()))
+            .cloned()
+            .sorted()
+            .collect()
+    }
+}
+
+struct TreeReader<I: Input> {
+    input: Peekable<Enumerate<I>>,
+    indentation: Option<Indentation>,
+}
+impl<I: Input> TreeReader<I> {
+    fn next_tree(&mut self, line: usize, expected_level: usize) -> ParseResult<Option<Tree>> {
+        let mut attributes = HashMap::<String, Attribute>::default();
+        while let Some((key, value)) = self.next_attribute(expected_level)? {
+            if let Some(first) = attributes.get(&key) {
+                return Err(ParseError::DuplicateAttribute {
+                    key,
+                    first: first.line,
+                    second: value.line,
+                });
+            }
+            attributes.insert(key, value);
+        }
+
+        Ok(match !attributes.is_empty() {
+            true => Some(Tree { line, attributes }),
+            false => None,
+        })
+    }
+
+    fn next_attribute(
+        &mut self,
+        expected_level: usize,
+    ) -> ParseResult<Option<(String, Attribute)>> {
+        let (idx, next) = match self.input.peek() {
+            Some((idx, Ok(next))) => (*idx, next),
+            Some((_, Err(_))) => {
+                return Err(self.input.next().unwrap().1.err().unwrap().into());
+            }
+            None => return Ok(None),
+        };
+        let line = idx + 1;
+
+        if next.is_empty() {
+            return Ok(None);
+        }
+
+        let prefix = &next[..next.len() - next.trim_start_matches([' ', '\t']).len()];
+        let level = match prefix.is_empty() {
+            true => 0,
+            false => {
+                let indentation =
+                    match self.indentation {
+                        Some(indentation) => indentation,
+                        None => *self
+                            .indentation
+                            .insert(Indentation::detect(prefix).ok_or_else(|| {
+                                ParseError::MixedIndentation(line, prefix.to_string())
+                            })?),
+                    };
+
+                indentation
+                    .level(prefix)
+                    .ok_or_else(|| ParseError::BadIndentation {
+                        line,
+                        expected: indentation,
+                        found: prefix.to_string(),
+                    })?
+            }
+        };
+        if level < expected_level {
+            return Ok(None);
+        }
+        if level > expected_level {
+            return Err(ParseError::UnexpectedIndentation(line, next.to_string()));
+        }
+
+        let next = self.input.next().unwrap().1.unwrap();
+        let (key, value) = next
+            .split_once(':')
+            .ok_or_else(|| ParseError::MissingSeparator(line, next.clone()))?;
+
+        let key = key.trim().to_string();
+        let value = value.trim().to_string();
+        let subtree = self.next_tree(line, expected_level + 1)?;
+        let value = match subtree {
+            Some(subtree) => TreeValue::Subtree(value, Box::new(subtree)),
+            None => TreeValue::Value(value),
+        };
+
+        Ok(Some((key, Attribute { line, value })))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Indentation {
+    Tabs,
+    Spaces(usize),
+}
+impl Indentation {
+    fn detect(prefix: &str) -> Option<Self> {
+        if prefix.bytes().all(|x| x == b'\t') {
+            return Some(Indentation::Tabs);
+        }
+        if prefix.bytes().all(|x| x == b' ') {
+            return Some(Indentation::Spaces(prefix.len()));
+        }
+
+        None
+    }
+
+    fn level(self, prefix: &str) -> Option<usize> {
+        match (self, Self::detect(prefix)?) {
+            (Indentation::Tabs, Indentation::Tabs) => Some(prefix.len()),
+            (Indentation::Spaces(unit), Indentation::Spaces(found)) if found % unit == 0 => {
+                Some(found / unit)
+            }
+            _ => None,
+        }
+    }
+}
+impl std::fmt::Display for Indentation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Indentation::Tabs => write!(f, "one tab"),
+            Indentation::Spaces(1) => write!(f, "one space"),
+            Indentation::Spaces(count) => write!(f, "{count} spaces"),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Attribute {
+    line: usize,
+    value: TreeValue,
+}
+
+#[derive(Debug)]
+enum TreeValue {
+    Value(String),
+    Subtree(String, Box<Tree>),
+}
+impl TreeValue {
+    fn get_value(&self) -> Result<&str, ExtraneousSubtree> {
+        match self {
+            TreeValue::Value(value) => Ok(value),
+            TreeValue::Subtree(_, _) => Err(ExtraneousSubtree),
+        }
+    }
+
+    fn get_subtree(&self) -> Result<(&str, &Tree), MissingSubtree> {
+        match self {
+            TreeValue::Value(_) => Err(MissingSubtree),
+            TreeValue::Subtree(main, tree) => Ok((main, tree.as_ref())),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Missing {0:?} attribute")]
+struct MissingAttribute(&'static str);
+
+#[derive(thiserror::Error, Debug)]
+#[error("Unexpected subtree")]
+struct ExtraneousSubtree;
+
+#[derive(thiserror::Error, Debug)]
+#[error("Missing subtree")]
+struct MissingSubtree;
+
+#[derive(thiserror::Error, Debug)]
+enum ParseError {
+    #[error("{0}")]
+    Input(#[from] InputError),
+    #[error("Line {0}: unexpected indentation at {1:?}")]
+    UnexpectedIndentation(usize, String),
+    #[error("Line {0}: indentation {1:?} mixes tabs and spaces")]
+    MixedIndentation(usize, String),
+    #[error("Line {line}: expected indentation in multiples of {expected}, found {found:?}")]
+    BadIndentation {
+        line: usize,
+        expected: Indentation,
+        found: String,
+    },
+    #[error("Line {0}: missing {sep:?} separator at {1:?}", sep = ':')]
+    MissingSeparator(usize, String),
+    #[error("Attribute {key:?} defined on line {first} and again on line {second}")]
+    DuplicateAttribute {
+        key: String,
+        first: usize,
+        second: usize,
+    },
+}
+impl From<ParseError> for crate::Error {
+    fn from(value: ParseError) -> Self {
+        crate::Error::Parsing(value.into())
+    }
+}
+type ParseResult<T> = Result<T, ParseError>;
+
+#[derive(Debug)]
+struct Config {
+    verbose: bool,
+    strict: bool,
+    top: usize,
+    /// Relief of part 1, part 2 having none.
+    relief: Relief,
+    /// Part 2 saves its state every this many rounds into the given file.
+    checkpoint: Option<(usize, PathBuf)>,
+    /// Part 2 continues from this checkpoint instead of the input.
+    resume: Option<PathBuf>,
+}
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            verbose: false,
+            strict: false,
+            top: 2,
+            relief: Relief::DivideBy(3),
+            checkpoint: None,
+            resume: None,
+        }
+    }
+}
+impl Config {
+    fn from_params(params: &crate::Params) -> crate::Result<Self> {
+        let default = Config::default();
+
+        let checkpoint = match params.parse("checkpoint-every")? {
+            Some(0) => {
+                return Err(ParamError {
+                    name: "checkpoint-every",
+                    value: "0".to_string(),
+                    reason: "at least one round needed".to_string(),
+                }
+                .into())
+            }
+            Some(every) => Some((
+                every,
+                params.parse("checkpoint-file")?.ok_or(ParamError {
+                    name: "checkpoint-file",
+                    value: String::new(),
+                    reason: "required by --checkpoint-every".to_string(),
+                })?,
+            )),
+            None => None,
+        };
+
+        Ok(Config {
+            verbose: params.verbose(),
+            strict: params.flag("strict"),
+            top: params.parse("top")?.unwrap_or(default.top),
+            relief: params.parse("relief")?.unwrap_or(default.relief),
+            checkpoint,
+            resume: params.parse("resume")?,
+        })
+    }
+}
+
+/// Builds monkeys record by record, so only one record's tree is alive at a
+/// time and a failure still tells how far parsing got.
+fn parse_records<I: Input>(input: I, config: &Config) -> Result<Vec<Monkey>, PartialParse> {
+    let mut monkeys = vec![];
+
+    for (idx, tree) in Tree::input(input).enumerate() {
+        let monkey = tree
+            .map_err(crate::Error::from)
+            .and_then(|(_, tree)| Ok(Monkey::from_tree_checked(&tree, idx, config)?));
+
+        match monkey {
+            Ok(monkey) => monkeys.push(monkey),
+            Err(error) => {
+                return Err(PartialParse {
+                    parsed: monkeys.len(),
+                    error,
+                })
+            }
+        }
+    }
+
+    Ok(monkeys)
+}
+
+fn parse<I: Input>(input: I, config: &Config) -> crate::Result<Monkeys> {
+    let monkeys = parse_records(input, config).map_err(|partial| {
+        if config.verbose {
+            eprintln!("{partial}");
+        }
+        partial.error
+    })?;
+
+    if config.verbose {
+        eprintln!("{} monkeys parsed\n", monkeys.len());
+    }
+
+    Ok(Monkeys::new(monkeys)?)
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("{parsed} monkeys parsed, then error in record {}: {error}", parsed + 1)]
+struct PartialParse {
+    parsed: usize,
+    error: crate::Error,
+}
+
+fn answer<I: Input>(input: I, config: &Config) -> crate::Result<Answer<u64>> {
+    let monkeys = parse(input, config)?;
+
+    let runs = [
+        SimConfig {
+            rounds: 20,
+            relief: config.relief,
+        },
+        SimConfig {
+            rounds: 10_000,
+            relief: Relief::None,
+        },
+    ];
+
+    if config.checkpoint.is_some() || config.resume.is_some() {
+        let monkey_business = monkeys.simulate(runs[0], config)?;
+
+        let mut simulator = match &config.resume {
+            Some(path) => Simulator::load(io::BufReader::new(
+                File::open(path).map_err(CheckpointError::from)?,
+            ))?,
+            None => Simulator::new(monkeys.modular(), Relief::None)?,
+        };
+        match &config.checkpoint {
+            Some((every, path)) => simulator.run_checkpointed(runs[1].rounds, *every, path)?,
+            None => simulator.run(runs[1].rounds.saturating_sub(simulator.rounds)),
+        }
+
+        return Ok(Answer {
+            part1: monkey_business,
+            part2: simulator.monkey_business(config.top)?,
+        });
+    }
+
+    let [monkey_business, stressed_monkey_business] = match config.verbose {
+        true => [
+            monkeys.simulate(runs[0], config)?,
+            monkeys.simulate(runs[1], config)?,
+        ],
+        false => {
+            let results = monkeys.run_many(&runs);
+            [
+                results[0].monkey_business(config.top)?,
+                results[1].monkey_business(config.top)?,
+            ]
+        }
+    };
+
+    Ok(Answer {
+        part1: monkey_business,
+        part2: stressed_monkey_business,
+    })
+}
+
+pub fn main() -> crate::Exit {
+    run().into()
+}
+
+pub fn solve(input: crate::input::InputImpl, params: &crate::Params) -> crate::Result<String> {
+    let config = Config::from_params(params)?;
+    Ok(answer(input, &config)?.to_string())
+}
+
+fn run() -> crate::Result<()> {
+    let config = Config::from_params(&crate::params()?)?;
+    crate::main_impl(DAY, |input| answer(input, &config))
+}
+
+#[test]
+fn d11_test() {
+    assert_eq!(
+        answer(crate::input(DAY, true), &Config::default()).unwrap(),
+        Answer {
+            part1: 10605,
+            part2: 2713310158
+        }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::input_from_str;
+
+    use super::*;
+
+    fn monkeys(input: &str) -> crate::Result<Monkeys> {
+        monkeys_with(input, &Config::default())
+    }
+
+    fn monkeys_with(input: &str, config: &Config) -> crate::Result<Monkeys> {
+        parse(input_from_str(input), config)
+    }
+
+    fn monkey(name: usize, if_true: usize, if_false: usize) -> String {
+        format!(
+            "Monkey {name}:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey {if_true}
+    If false: throw to monkey {if_false}
+"
+        )
+    }
+
+    #[test]
+    fn example_is_valid() {
+        assert_eq!(example().0.len(), 4);
+    }
+
+    #[test]
+    fn out_of_range_target() {
+        let input = [monkey(0, 1, 9), monkey(1, 0, 0)].join("\n");
+        let error = monkeys(&input).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Monkey 0 throws to monkey 9, but there are only 2 monkeys"
+        );
+    }
+
+    fn example() -> Monkeys {
+        monkeys(&std::fs::read_to_string("input/examples/d11").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn divide_by_one_is_no_relief() {
+        let input = [
+            "Monkey 0:",
+            "  Starting items: 79, 98",
+            "  Operation: new = old + 19",
+            "  Test: divisible by 23",
+            "    If true: throw to monkey 1",
+            "    If false: throw to monkey 1",
+            "",
+            "Monkey 1:",
+            "  Starting items: 54, 65, 75, 74",
+            "  Operation: new = old + 6",
+            "  Test: divisible by 19",
+            "    If true: throw to monkey 0",
+            "    If false: throw to monkey 0",
+        ]
+        .join("\n");
+        let monkeys = monkeys(&input).unwrap();
+
+        let mut divided = Simulator::new(monkeys.clone(), Relief::DivideBy(1)).unwrap();
+        divided.run(20);
+        let mut relieved = Simulator::new(monkeys, Relief::None).unwrap();
+        relieved.run(20);
+
+        assert_eq!(divided.activity, relieved.activity);
+    }
+
+    #[test]
+    fn relief_param() {
+        let relief = |value| {
+            let params = crate::Params::default().with("relief", Some(value));
+            Config::from_params(&params).map(|config| config.relief)
+        };
+        assert_eq!(relief("2").unwrap(), Relief::DivideBy(2));
+        assert_eq!(relief("none").unwrap(), Relief::None);
+        assert!(relief("0").is_err());
+        assert!(relief("half").is_err());
+        assert_eq!(
+            Config::from_params(&crate::Params::default())
+                .unwrap()
+                .relief,
+            Relief::DivideBy(3)
+        );
+
+        let config = Config {
+            relief: Relief::DivideBy(7),
+            ..Config::default()
+        };
+        let mut relieved = Simulator::new(example(), Relief::DivideBy(7)).unwrap();
+        relieved.run(20);
+        let answer = answer(crate::input(DAY, true), &config).unwrap();
+        assert_eq!(answer.part1, relieved.monkey_business(2).unwrap());
+        assert_ne!(answer.part1, 10605);
+        assert_eq!(answer.part2, 2713310158);
+    }
+
+    #[test]
+    fn modular_rejects_division() {
+        let monkeys = example().modular();
+
+        assert_eq!(
+            Simulator::new(monkeys.clone(), Relief::DivideBy(3))
+                .unwrap_err()
+                .0,
+            3
+        );
+        assert!(Simulator::new(monkeys, Relief::None).is_ok());
+    }
+
+    #[test]
+    fn round() {
+        let mut simulator = Simulator::new(example(), Relief::DivideBy(3)).unwrap();
+        simulator.round();
+
+        let items = simulator
+            .monkeys
+            .iter()
+            .map(|monkey| monkey.items.iter().map(|item| item.0).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            items,
+            [
+                vec![20, 23, 27, 26],
+                vec![2080, 25, 167, 207, 401, 1046],
+                vec![],
+                vec![],
+            ]
+        );
+        assert_eq!(simulator.activity, [2, 4, 3, 5]);
+        assert_eq!(simulator.rounds, 1);
+    }
+
+    #[test]
+    fn monkey_business_with_ties() {
+        let mut simulator = Simulator::new(example(), Relief::DivideBy(3)).unwrap();
+        simulator.activity = vec![7, 3, 7, 7];
+
+        assert_eq!(simulator.monkey_business(1).unwrap(), 7);
+        assert_eq!(simulator.monkey_business(2).unwrap(), 49);
+        assert_eq!(simulator.monkey_business(3).unwrap(), 343);
+        assert_eq!(simulator.monkey_business(4).unwrap(), 1029);
+        assert_eq!(simulator.busiest(2), [(0, 7), (2, 7)]);
+        assert_eq!(simulator.busiest(4), [(0, 7), (2, 7), (3, 7), (1, 3)]);
+    }
+
+    #[test]
+    fn monkey_business_top_k() {
+        let mut simulator = Simulator::new(example(), Relief::DivideBy(3)).unwrap();
+        simulator.run(20);
+
+        assert_eq!(simulator.monkey_business(1).unwrap(), 105);
+        assert_eq!(simulator.monkey_business(2).unwrap(), 10605);
+        assert_eq!(simulator.monkey_business(4).unwrap(), 105 * 101 * 95 * 7);
+        assert_eq!(simulator.monkey_business(10).unwrap(), 105 * 101 * 95 * 7);
+        assert_eq!(simulator.busiest(1), [(3, 105)]);
+    }
+
+    #[test]
+    fn monkey_business_overflow() {
+        let mut simulator = Simulator::new(example(), Relief::DivideBy(3)).unwrap();
+        simulator.activity = vec![1 << 40, 1 << 20, 1 << 23, 1];
+
+        assert_eq!(simulator.monkey_business(2).unwrap(), 1 << 63);
+        assert_eq!(simulator.monkey_business(3).unwrap_err().0, 3);
+
+        simulator.activity = vec![u64::MAX, u64::MAX, u64::MAX, u64::MAX];
+        assert_eq!(simulator.monkey_business(1).unwrap(), u64::MAX);
+        assert_eq!(simulator.monkey_business(4).unwrap_err().0, 4);
+    }
+
+    #[test]
+    fn report() {
+        let mut simulator = Simulator::new(example(), Relief::DivideBy(3)).unwrap();
+
+        let mut reports = vec![];
+        for _ in 0..20 {
+            simulator.round();
+            if simulator.rounds == 1 || simulator.rounds == 20 {
+                reports.push(simulator.holdings());
+            }
+        }
+
+        assert_eq!(
+            reports,
+            [
+                [
+                    "Monkey 0: 20, 23, 27, 26",
+                    "Monkey 1: 2080, 25, 167, 207, 401, 1046",
+                    "Monkey 2: ",
+                    "Monkey 3: ",
+                ]
+                .join("\n"),
+                [
+                    "Monkey 0: 10, 12, 14, 26, 34",
+                    "Monkey 1: 245, 93, 53, 199, 115",
+                    "Monkey 2: ",
+                    "Monkey 3: ",
+                ]
+                .join("\n"),
+            ]
+        );
+        assert_eq!(
+            simulator.inspections(),
+            [
+                "Monkey 0 inspected items 101 times.",
+                "Monkey 1 inspected items 95 times.",
+                "Monkey 2 inspected items 7 times.",
+                "Monkey 3 inspected items 105 times.",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn indentation() {
+        let input = std::fs::read_to_string("input/examples/d11").unwrap();
+        let reindent = |unit: &str| {
+            input
+                .lines()
+                .map(|line| {
+                    let trimmed = line.trim_start();
+                    let level = (line.len() - trimmed.len()) / 2;
+                    format!("{}{trimmed}", unit.repeat(level))
+                })
+                .join("\n")
+        };
+        let holdings = |monkeys: Monkeys| {
+            let mut simulator = Simulator::new(monkeys, Relief::DivideBy(3)).unwrap();
+            simulator.run(20);
+            (simulator.holdings(), simulator.activity)
+        };
+
+        let expected = holdings(example());
+        assert_eq!(holdings(monkeys(&reindent("\t")).unwrap()), expected);
+        assert_eq!(holdings(monkeys(&reindent("    ")).unwrap()), expected);
+
+        let mixed = reindent("  ").replacen("    If false", "\t\tIf false", 1);
+        assert_eq!(
+            monkeys(&mixed).unwrap_err().to_string(),
+            "Line 6: expected indentation in multiples of 2 spaces, found \"\\t\\t\""
+        );
+
+        let odd = input.replacen("    If true", "     If true", 1);
+        assert_eq!(
+            monkeys(&odd).unwrap_err().to_string(),
+            "Line 5: expected indentation in multiples of 2 spaces, found \"     \""
+        );
+
+        let mixed_prefix = input.replacen("  Starting", " \tStarting", 1);
+        assert_eq!(
+            monkeys(&mixed_prefix).unwrap_err().to_string(),
+            "Line 2: indentation \" \\t\" mixes tabs and spaces"
+        );
+    }
+
+    #[test]
+    fn duplicated_attribute() {
+        let input = std::fs::read_to_string("input/examples/d11").unwrap();
+        let input = input.replacen(
+            "  Test: divisible by 19",
+            "  Test: divisible by 19\n    If true: throw to monkey 2\n    If false: throw to monkey 0\n  Test: divisible by 19",
+            1,
+        );
+
+        assert_eq!(
+            monkeys(&input).unwrap_err().to_string(),
+            "Attribute \"Test\" defined on line 11 and again on line 14"
+        );
+    }
+
+    #[test]
+    fn unknown_attribute() {
+        let input = std::fs::read_to_string("input/examples/d11").unwrap();
+        let input = input.replacen("Operation:", "Operatoin:", 1);
+        let strict = Config {
+            strict: true,
+            ..Config::default()
+        };
+
+        assert_eq!(
+            monkeys(&input).unwrap_err().to_string(),
+            "monkey 0 (line 1): Missing \"Operation\" attribute"
+        );
+        assert_eq!(
+            monkeys_with(&input, &strict).unwrap_err().to_string(),
+            "monkey 0 (line 1): Unknown attributes [\"Operatoin\"], and Missing \"Operation\" attribute"
+        );
+        assert_eq!(
+            monkeys_with(
+                &std::fs::read_to_string("input/examples/d11").unwrap(),
+                &strict
+            )
+            .unwrap()
+            .0
+            .len(),
+            4
+        );
+    }
+
+    #[test]
+    fn located_errors() {
+        let input = std::fs::read_to_string("input/examples/d11").unwrap();
+
+        let operation = input.replacen("new = old * old", "new = old ^ old", 1);
+        assert_eq!(
+            monkeys(&operation).unwrap_err().to_string(),
+            "monkey 2 (line 17): Invalid operation: \"old ^ old\""
+        );
+
+        let target = input.replacen("throw to monkey 0", "throw to monkey zero", 1);
+        assert_eq!(
+            monkeys(&target).unwrap_err().to_string(),
+            "monkey 1 (line 13): invalid digit found in string"
+        );
+
+        let divisible = input.replacen("divisible by 17", "divisible by x", 1);
+        assert_eq!(
+            monkeys(&divisible).unwrap_err().to_string(),
+            "monkey 3 (line 25): invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn partial_parse() {
+        let mut input = (0..8)
+            .map(|idx| monkey(idx, (idx + 1) % 8, (idx + 7) % 8))
+            .join("\n");
+        assert_eq!(
+            parse_records(input_from_str(&input), &Config::default())
+                .unwrap()
+                .len(),
+            8
+        );
+
+        input = input.replace(
+            "Monkey 7:\n  Starting items: 79, 98",
+            "Monkey 7:\n  Starting items: 79; 98",
+        );
+        let partial = parse_records(input_from_str(&input), &Config::default()).unwrap_err();
+        assert_eq!(partial.parsed, 7);
+        assert_eq!(
+            partial.to_string(),
+            "7 monkeys parsed, then error in record 8: monkey 7 (line 51): \
+             invalid digit found in string, invalid number on item list \"79; 98\""
+        );
+
+        assert_eq!(example().0.len(), 4);
+        assert_eq!(
+            answer(crate::input(DAY, true), &Config::default()).unwrap(),
+            Answer {
+                part1: 10605,
+                part2: 2713310158
+            }
+        );
+    }
+
+    #[test]
+    fn throw_events() {
+        let mut simulator = Simulator::new(example().tracked(), Relief::DivideBy(3)).unwrap();
+
+        let mut events = vec![];
+        simulator.run_with(20, |event| events.push(event));
+        assert_eq!(events.len(), 101 + 95 + 7 + 105);
+        assert_eq!(events.len() as u64, simulator.activity.iter().sum::<u64>());
+
+        let hops = events
+            .iter()
+            .filter(|event| event.item_index == Some(0))
+            .take(3)
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            hops,
+            [
+                ThrowEvent {
+                    round: 1,
+                    from: 0,
+                    to: 3,
+                    item_index: Some(0),
+                    worry: Some(500),
+                },
+                ThrowEvent {
+                    round: 1,
+                    from: 3,
+                    to: 1,
+                    item_index: Some(0),
+                    worry: Some(167),
+                },
+                ThrowEvent {
+                    round: 2,
+                    from: 1,
+                    to: 2,
+                    item_index: Some(0),
+                    worry: Some(57),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn run_many() {
+        fn assert_send<T: Send>() {}
+        assert_send::<SimpleItem>();
+        assert_send::<ModuleItem>();
+        assert_send::<Tracked<ModuleItem>>();
+        assert_send::<Monkey<ModuleItem>>();
+        assert_send::<Simulator<SimpleItem>>();
+
+        let monkeys = example();
+        let configs = [
+            SimConfig {
+                rounds: 20,
+                relief: Relief::DivideBy(3),
+            },
+            SimConfig {
+                rounds: 10_000,
+                relief: Relief::None,
+            },
+            SimConfig {
+                rounds: 500,
+                relief: Relief::None,
+            },
+        ];
+
+        let parallel = monkeys.run_many(&configs);
+        // Each simulated from scratch, without forking.
+        let sequential = configs
+            .iter()
+            .map(|config| match config.relief {
+                Relief::DivideBy(_) => {
+                    let mut simulator = Simulator::new(monkeys.clone(), config.relief).unwrap();
+                    simulator.run(config.rounds);
+                    simulator.result()
+                }
+                Relief::None => {
+                    let mut simulator = Simulator::new(monkeys.modular(), config.relief).unwrap();
+                    simulator.run(config.rounds);
+                    simulator.result()
+                }
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(parallel, sequential);
+        assert_eq!(parallel[0].monkey_business(2).unwrap(), 10605);
+        assert_eq!(parallel[1].monkey_business(2).unwrap(), 2713310158);
+        assert_eq!(parallel[2].rounds, 500);
+
+        let mut simulator = Simulator::new(monkeys, Relief::DivideBy(3)).unwrap();
+        simulator.run(20);
+        let fork = simulator.fork();
+        assert_eq!(fork.activity, [0, 0, 0, 0]);
+        assert_eq!(fork.holdings(), simulator.holdings());
+    }
+
+    #[test]
+    fn checkpoint() {
+        let mut straight = Simulator::new(example().modular(), Relief::None).unwrap();
+        straight.run(10_000);
+
+        let mut first = Simulator::new(example().modular(), Relief::None).unwrap();
+        first.run(5_000);
+        let mut saved = vec![];
+        first.save(&mut saved).unwrap();
+
+        let mut resumed = Simulator::<ModuleItem>::load(&saved[..]).unwrap();
+        assert_eq!(resumed.rounds, 5_000);
+        resumed.run(5_000);
+
+        assert_eq!(resumed.rounds, straight.rounds);
+        assert_eq!(resumed.activity, straight.activity);
+        assert_eq!(
+            resumed.monkey_business(2).unwrap(),
+            straight.monkey_business(2).unwrap()
+        );
+        assert_eq!(resumed.monkey_business(2).unwrap(), 2713310158);
+
+        let mut simple = Simulator::new(example(), Relief::DivideBy(3)).unwrap();
+        simple.run(3);
+        let mut saved = vec![];
+        simple.save(&mut saved).unwrap();
+        let loaded = Simulator::<SimpleItem>::load(&saved[..]).unwrap();
+        assert_eq!(loaded.holdings(), simple.holdings());
+        assert_eq!(loaded.relief, Relief::DivideBy(3));
+
+        let truncated = &saved[..saved.len() / 2];
+        assert!(Simulator::<SimpleItem>::load(truncated).is_err());
+        assert!(Simulator::<ModuleItem>::load(&saved[..]).is_err());
+    }
+
+    #[test]
+    fn checkpoint_every() {
+        let params = crate::Params::default().with("checkpoint-file", Some("d11.checkpoint"));
+        let every = |rounds| params.clone().with("checkpoint-every", Some(rounds));
+
+        let config = Config::from_params(&every("100")).unwrap();
+        assert_eq!(
+            config.checkpoint,
+            Some((100, PathBuf::from("d11.checkpoint")))
+        );
+        let error = Config::from_params(&every("0")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid value \"0\" for \"checkpoint-every\": at least one round needed"
+        );
+    }
+
+    #[test]
+    fn corrupt_checkpoint() {
+        fn saved<T: Item + Checkpoint>(simulator: Simulator<T>) -> String {
+            let mut saved = vec![];
+            simulator.save(&mut saved).unwrap();
+            String::from_utf8(saved).unwrap()
+        }
+        let simple = saved(Simulator::new(example(), Relief::DivideBy(3)).unwrap());
+        let modular = saved(Simulator::new(example().modular(), Relief::None).unwrap());
+
+        let by_zero = simple.replace("relief: divide by 3", "relief: divide by 0");
+        assert!(matches!(
+            Simulator::<SimpleItem>::load(by_zero.as_bytes()),
+            Err(CheckpointError::Format(3, "relief", _))
+        ));
+
+        let many = simple.replace("monkeys: 4", "monkeys: 18446744073709551615");
+        assert!(matches!(
+            Simulator::<SimpleItem>::load(many.as_bytes()),
+            Err(CheckpointError::Truncated("operation"))
+        ));
+
+        let wrong_modulus = modular.replace("%23", "%29");
+        assert!(matches!(
+            Simulator::<ModuleItem>::load(wrong_modulus.as_bytes()),
+            Err(CheckpointError::Moduli(0, _))
+        ));
+        assert!(Simulator::<ModuleItem>::load(modular.as_bytes()).is_ok());
+    }
+
+    #[test]
+    fn self_throw() {
+        let input = [monkey(0, 1, 1), monkey(1, 0, 1)].join("\n");
+        let error = monkeys(&input).unwrap_err();
+
+        assert_eq!(error.to_string(), "Monkey 1 throws to itself");
+    }
+}
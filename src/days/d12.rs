@@ -0,0 +1,1054 @@
+use std::{cmp::Ordering, num::ParseIntError, path::PathBuf, str::FromStr};
+
+use itertools::Itertools;
+
+use crate::{
+    grid::{Adjacency, Coord, Grid},
+    input::{Input, InputError},
+    params::ParamError,
+    pgm,
+    search::{self, DistanceMap},
+    Answer,
+};
+
+pub const DAY: u32 = 12;
+
+/// Height of the filler for the missing cells of short lines, no step enters
+/// or leaves it.
+const IMPASSABLE: u16 = u16::MAX;
+
+#[derive(Debug, Default, Clone)]
+struct HeightMap {
+    heights: Grid<u16>,
+    /// Every `S` kept by the start [`Policy`].
+    starts: Vec<Coord>,
+    /// Every `E` kept by the end [`Policy`].
+    ends: Vec<Coord>,
+    rules: Rules,
+    /// Lines that were too short and got padded.
+    padded: Vec<PaddedLine>,
+}
+impl HeightMap {
+    /// Parses a map. With `pad` lines shorter than the widest one are filled
+    /// with impassable cells instead of being an error.
+    fn input<I: Input>(input: I, markers: Markers, pad: bool) -> ParseResult<HeightMap> {
+        let lines = input.collect::<Result<Vec<_>, _>>()?;
+        let Some(first) = lines.first() else {
+            return Err(ParseError::EmptyMap);
+        };
+
+        let width = match pad {
+            true => lines.iter().map(String::len).max().unwrap_or_default(),
+            false => first.len(),
+        };
+        let mut heights = vec![];
+        let mut starts = vec![];
+        let mut ends = vec![];
+        let mut padded = vec![];
+
+        for (y, line) in lines.iter().enumerate() {
+            let line = line.as_bytes();
+            if pad && line.len() < width {
+                padded.push(PaddedLine {
+                    line: y + 1,
+                    width: line.len(),
+                });
+            }
+
+            Self::append_line(y, width, &mut heights, line, &mut starts, &mut ends, pad)?;
+        }
+
+        if starts.is_empty() {
+            return Err(ParseError::MissingStart);
+        }
+        if ends.is_empty() {
+            return Err(ParseError::MissingEnd);
+        }
+
+        Ok(HeightMap {
+            heights: Grid::new(width, heights),
+            starts: markers.starts.select(starts, ParseError::DoubleStart)?,
+            ends: markers.ends.select(ends, ParseError::DoubleEnd)?,
+            rules: Rules::default(),
+            padded,
+        })
+    }
+
+    fn append_line(
+        y: usize,
+        width: usize,
+        heights: &mut Vec<u16>,
+        line: &[u8],
+        starts: &mut Vec<Coord>,
+        ends: &mut Vec<Coord>,
+        pad: bool,
+    ) -> ParseResult<()> {
+        if line.len() > width || (line.len() < width && !pad) {
+            return Err(ParseError::BadWidth(line.len(), width));
+        }
+
+        for (x, char) in line.iter().copied().enumerate() {
+            let height = match char {
+                b'S' => {
+                    starts.push((x, y));
+                    b'a'
+                }
+                b'E' => {
+                    ends.push((x, y));
+                    b'z'
+                }
+                other => other,
+            };
+
+            if !height.is_ascii_lowercase() {
+                return Err(ParseError::BadChar(char));
+            }
+            heights.push((height - b'a').into());
+        }
+        heights.resize(heights.len() + width - line.len(), IMPASSABLE);
+
+        Ok(())
+    }
+
+    /// Parses a map given as comma separated elevations from 0 to 255, with
+    /// the start and end cells given separately.
+    fn numeric<I: Input>(input: I, start: Coord, end: Coord) -> ParseResult<HeightMap> {
+        let mut width = None;
+        let mut heights = vec![];
+
+        for (y, line) in input.enumerate() {
+            let line = line?;
+            let row = line
+                .split(',')
+                .map(|value| {
+                    let value = value.trim();
+                    let height = value
+                        .parse::<u8>()
+                        .map_err(|e| ParseError::BadElevation(y + 1, value.to_string(), e))?;
+                    Ok(height.into())
+                })
+                .collect::<ParseResult<Vec<u16>>>()?;
+
+            let width = *width.get_or_insert(row.len());
+            if row.len() != width {
+                return Err(ParseError::BadWidth(row.len(), width));
+            }
+            heights.extend(row);
+        }
+
+        let Some(width) = width else {
+            return Err(ParseError::EmptyMap);
+        };
+        let heights = Grid::new(width, heights);
+
+        for (marker, coord) in [("Start", start), ("End", end)] {
+            if !heights.contains(coord) {
+                return Err(ParseError::OutsideMap(marker, coord));
+            }
+        }
+
+        Ok(HeightMap {
+            heights,
+            starts: vec![start],
+            ends: vec![end],
+            ..HeightMap::default()
+        })
+    }
+
+    /// Cells one allowed step away from `from`. Navigating in reverse walks
+    /// the allowed steps backwards, so the limits on climbing and dropping
+    /// swap roles.
+    fn steps(&self, from: Coord, navigation: Navigation) -> impl Iterator<Item = Coord> + '_ {
+        let Self { heights, rules, .. } = self;
+        let climbs = move |from: Coord, to: Coord| {
+            let (from, to) = (heights[from], heights[to]);
+            from != IMPASSABLE && to != IMPASSABLE && rules.allows(from, to)
+        };
+
+        heights
+            .neighbors(from, rules.adjacency())
+            .filter(move |&to| match navigation {
+                Navigation::Forward => climbs(from, to),
+                Navigation::Reverse => climbs(to, from),
+            })
+    }
+
+    /// Lower bound of the steps from `from` to the closest end: the Manhattan
+    /// distance, or the Chebyshev distance when diagonal steps are allowed.
+    fn estimate(&self, (x, y): Coord) -> usize {
+        self.ends
+            .iter()
+            .map(|&(gx, gy)| {
+                let (dx, dy) = (x.abs_diff(gx), y.abs_diff(gy));
+                match self.rules.diagonals {
+                    false => dx + dy,
+                    true => dx.max(dy),
+                }
+            })
+            .min()
+            .unwrap_or_default()
+    }
+
+    /// Elevations as shades of gray, from black for `a` to white for `z`, or
+    /// the highest numeric elevation. Impassable cells are black too.
+    fn elevation_image(&self) -> Grid<u8> {
+        let passable = self.heights.cells().filter(|&&x| x != IMPASSABLE);
+        let max = passable.max().copied().unwrap_or_default().max(25);
+
+        self.heights.map(|&height| match height {
+            IMPASSABLE => 0,
+            height => pgm::shade(height.into(), max.into(), 0),
+        })
+    }
+
+    /// Draws the map with every step of `path` as an arrow pointing to the next
+    /// cell. The last cell is drawn as `E` when it is an end, `*` otherwise.
+    fn render_path(&self, path: &[Coord]) -> String {
+        let mut grid = vec![vec!['.'; self.heights.width()]; self.heights.height()];
+
+        for step in path.windows(2) {
+            let [(x, y), to] = [step[0], step[1]];
+            grid[y][x] = match (to.0.cmp(&x), to.1.cmp(&y)) {
+                (Ordering::Greater, Ordering::Equal) => '>',
+                (Ordering::Less, Ordering::Equal) => '<',
+                (Ordering::Equal, Ordering::Greater) => 'v',
+                (Ordering::Equal, _) => '^',
+                (dx, dy) if dx == dy => '\\',
+                _ => '/',
+            };
+        }
+        if let Some(&(x, y)) = path.last() {
+            grid[y][x] = if self.ends.contains(&(x, y)) {
+                'E'
+            } else {
+                '*'
+            };
+        }
+
+        grid.into_iter()
+            .map(|line| line.into_iter().collect::<String>() + "\n")
+            .collect()
+    }
+}
+
+/// Which steps are allowed while climbing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Rules {
+    diagonals: bool,
+    max_climb: u8,
+    /// `None` allows jumping down any height.
+    max_drop: Option<u8>,
+}
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            diagonals: false,
+            max_climb: 1,
+            max_drop: None,
+        }
+    }
+}
+impl Rules {
+    fn from_params(params: &crate::Params) -> crate::Result<Self> {
+        let default = Rules::default();
+
+        Ok(Rules {
+            diagonals: params.flag("diagonals"),
+            max_climb: params.parse("max-climb")?.unwrap_or(default.max_climb),
+            max_drop: params.parse("max-drop")?.or(default.max_drop),
+        })
+    }
+
+    fn adjacency(self) -> Adjacency {
+        match self.diagonals {
+            false => Adjacency::Orthogonal,
+            true => Adjacency::All,
+        }
+    }
+
+    fn allows(self, from: u16, to: u16) -> bool {
+        climbs(from, to, self.max_climb, self.max_drop)
+    }
+}
+
+/// Whether a step from height `from` to height `to` is allowed, climbing at
+/// most `max_climb` and dropping at most `max_drop`, any if not given.
+fn climbs(from: u16, to: u16, max_climb: u8, max_drop: Option<u8>) -> bool {
+    match to.checked_sub(from) {
+        Some(climb) => climb <= max_climb.into(),
+        None => from - to <= max_drop.map_or(u16::MAX, u16::from),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct PaddedLine {
+    line: usize,
+    width: usize,
+}
+impl std::fmt::Display for PaddedLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Line {} has only {} cells, padded with impassable cells",
+            self.line, self.width
+        )
+    }
+}
+
+/// What to do when a map has more than one `S` or `E`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Policy {
+    /// Multiple markers are an error.
+    #[default]
+    Strict,
+    First,
+    Last,
+    /// Every marker counts, the distance is to the closest one.
+    Multi,
+}
+impl FromStr for Policy {
+    type Err = UnknownPolicy;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "strict" => Ok(Policy::Strict),
+            "first" => Ok(Policy::First),
+            "last" => Ok(Policy::Last),
+            "multi" => Ok(Policy::Multi),
+            other => Err(UnknownPolicy(other.to_string())),
+        }
+    }
+}
+impl Policy {
+    /// Keeps the markers allowed by this policy, `found` being non empty and
+    /// in reading order.
+    fn select(self, mut found: Vec<Coord>, multiple: ParseError) -> ParseResult<Vec<Coord>> {
+        match self {
+            Policy::Strict if found.len() > 1 => return Err(multiple),
+            Policy::Strict | Policy::Multi => {}
+            Policy::First => found.truncate(1),
+            Policy::Last => found = found.split_off(found.len() - 1),
+        }
+
+        Ok(found)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Unknown marker policy {0:?}, expected strict, first, last or multi")]
+struct UnknownPolicy(String);
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Markers {
+    starts: Policy,
+    ends: Policy,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Navigation {
+    Forward,
+    Reverse,
+}
+
+#[derive(thiserror::Error, Debug)]
+enum ParseError {
+    #[error("{0}")]
+    Input(#[from] InputError),
+    #[error("Got line with width {0} but map is expected to have width {1}")]
+    BadWidth(usize, usize),
+    #[error("Empty height map")]
+    EmptyMap,
+    #[error("Missing start position in height map")]
+    MissingStart,
+    #[error("Missing end position in height map")]
+    MissingEnd,
+    #[error("Multiple start position in height map")]
+    DoubleStart,
+    #[error("Multiple end position in height map")]
+    DoubleEnd,
+    #[error("Char {as_char:?} is not a valid height", as_char = (*_0 as char))]
+    BadChar(u8),
+    #[error("Line {0} has bad elevation {1:?}: {2}")]
+    BadElevation(usize, String, ParseIntError),
+    #[error("{0} position {1:?} is outside of the height map")]
+    OutsideMap(&'static str, Coord),
+}
+impl From<ParseError> for crate::Error {
+    fn from(value: ParseError) -> Self {
+        crate::Error::Parsing(value.into())
+    }
+}
+type ParseResult<T> = Result<T, ParseError>;
+
+#[derive(thiserror::Error, Debug)]
+#[error("No path from {} to {}", cells(from), cells(to))]
+struct NoPath {
+    /// Empty when starting from any cell of height `a`.
+    from: Vec<Coord>,
+    to: Vec<Coord>,
+}
+fn cells(cells: &[Coord]) -> String {
+    match cells {
+        [] => "any lowest cell".to_string(),
+        [cell] => format!("{cell:?}"),
+        cells => format!(
+            "any of {}",
+            cells.iter().map(|x| format!("{x:?}")).join(", ")
+        ),
+    }
+}
+impl From<NoPath> for crate::Error {
+    fn from(value: NoPath) -> Self {
+        crate::Error::Semantic(value.into())
+    }
+}
+
+/// Length of a shortest path, if there is any.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Distance(Option<usize>);
+impl std::fmt::Debug for Distance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self, f)
+    }
+}
+impl std::fmt::Display for Distance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(distance) => distance.fmt(f),
+            None => write!(f, "unreachable"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Config {
+    /// Report missing paths as unreachable instead of failing.
+    lenient: bool,
+    /// Print the map with the shortest path to the end on stderr.
+    visualize: bool,
+    /// Print search statistics on stderr.
+    verbose: bool,
+    rules: Rules,
+    markers: Markers,
+    /// Pad short lines instead of failing.
+    lenient_grid: bool,
+    algorithm: Algorithm,
+    format: Format,
+    /// Where to save the elevations as an image.
+    dump_elevation: Option<PathBuf>,
+    /// Where to save the distances of part 1 as an image.
+    dump_distance: Option<PathBuf>,
+}
+impl Config {
+    fn from_params(params: &crate::Params) -> crate::Result<Self> {
+        Ok(Config {
+            lenient: params.flag("lenient"),
+            visualize: params.verbose() || params.flag("visualize"),
+            verbose: params.verbose(),
+            rules: Rules::from_params(params)?,
+            markers: Markers {
+                starts: params.parse("starts")?.unwrap_or_default(),
+                ends: params.parse("ends")?.unwrap_or_default(),
+            },
+            lenient_grid: params.flag("lenient-grid"),
+            algorithm: params.parse("algo")?.unwrap_or_default(),
+            format: Format::from_params(params)?,
+            dump_elevation: params.parse("dump-elevation")?,
+            dump_distance: params.parse("dump-distance")?,
+        })
+    }
+}
+
+/// How the map is written in the input.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Format {
+    /// Letters from `a` to `z`, with `S` and `E` marking start and end.
+    #[default]
+    Letters,
+    /// Comma separated numbers.
+    Numeric { start: Coord, end: Coord },
+}
+impl Format {
+    fn from_params(params: &crate::Params) -> Result<Self, ParamError> {
+        let coord = |name: &'static str| -> Result<Coord, ParamError> {
+            let error = |value: &str, reason: &str| ParamError {
+                name,
+                value: value.to_string(),
+                reason: reason.to_string(),
+            };
+            let value = params
+                .get(name)
+                .ok_or_else(|| error("", "required by numeric format"))?;
+            let (x, y) = value
+                .split_once(',')
+                .ok_or_else(|| error(value, "expected x,y"))?;
+
+            match (x.parse(), y.parse()) {
+                (Ok(x), Ok(y)) => Ok((x, y)),
+                _ => Err(error(value, "expected x,y")),
+            }
+        };
+
+        match params.get("format") {
+            None | Some("letters") => Ok(Format::Letters),
+            Some("numeric") => Ok(Format::Numeric {
+                start: coord("start")?,
+                end: coord("end")?,
+            }),
+            Some(other) => Err(ParamError {
+                name: "format",
+                value: other.to_string(),
+                reason: "expected letters or numeric".to_string(),
+            }),
+        }
+    }
+}
+
+/// How part 1 searches for the end.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Algorithm {
+    #[default]
+    Bfs,
+    AStar,
+}
+impl FromStr for Algorithm {
+    type Err = UnknownAlgorithm;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bfs" => Ok(Algorithm::Bfs),
+            "astar" => Ok(Algorithm::AStar),
+            other => Err(UnknownAlgorithm(other.to_string())),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Unknown search algorithm {0:?}, expected bfs or astar")]
+struct UnknownAlgorithm(String);
+
+fn answer<I: Input>(input: I, config: &Config) -> crate::Result<Answer<Distance>> {
+    let mut map = match config.format {
+        Format::Letters => HeightMap::input(input, config.markers, config.lenient_grid)?,
+        Format::Numeric { start, end } => HeightMap::numeric(input, start, end)?,
+    };
+    map.rules = config.rules;
+    for padded in &map.padded {
+        eprintln!("Warning: {padded}");
+    }
+
+    let (distances, stats) = match config.algorithm {
+        Algorithm::Bfs => search::bfs(&map.heights, map.starts.clone(), |from| {
+            map.steps(from, Navigation::Forward)
+        }),
+        Algorithm::AStar => search::astar(
+            &map.heights,
+            map.starts.clone(),
+            |from| map.steps(from, Navigation::Forward),
+            |from| map.estimate(from),
+            |from| map.ends.contains(&from),
+        ),
+    };
+    let (scenic, scenic_stats) = search::bfs(&map.heights, map.ends.clone(), |from| {
+        map.steps(from, Navigation::Reverse)
+    });
+    if config.verbose {
+        eprintln!("Part 1 using {:?}: {stats}", config.algorithm);
+        eprintln!("Part 2: {scenic_stats}");
+    }
+
+    if let Some(path) = &config.dump_elevation {
+        pgm::save(path, &map.elevation_image())?;
+    }
+    if let Some(path) = &config.dump_distance {
+        pgm::save(path, &distance_image(&distances))?;
+    }
+
+    let closest_end = closest(&distances, map.ends.iter().copied());
+    let distance_to_goal = closest_end.and_then(|end| distances.distance(end));
+    if config.visualize {
+        match closest_end.and_then(|end| distances.path_to(end)) {
+            Some(path) => eprint!("{}", map.render_path(&path)),
+            None => eprintln!("End is unreachable"),
+        }
+    }
+
+    let lowest = map.heights.coords().filter(|&x| map.heights[x] == 0);
+    let scenic_distance = closest(&scenic, lowest).and_then(|x| scenic.distance(x));
+
+    if !config.lenient {
+        distance_to_goal.ok_or(NoPath {
+            from: map.starts.clone(),
+            to: map.ends.clone(),
+        })?;
+        scenic_distance.ok_or(NoPath {
+            from: vec![],
+            to: map.ends.clone(),
+        })?;
+    }
+
+    Ok(Answer {
+        part1: Distance(distance_to_goal),
+        part2: Distance(scenic_distance),
+    })
+}
+
+/// Distances as shades of gray from the darkest for the sources to white for
+/// the farthest cells. Unreachable cells are black.
+fn distance_image(distances: &DistanceMap) -> Grid<u8> {
+    let max = distances.distances().cells().flatten().max();
+
+    distances.distances().map(|distance| match (distance, max) {
+        (Some(distance), Some(&max)) => pgm::shade(*distance, max, 1),
+        _ => 0,
+    })
+}
+
+/// The reached cell out of `cells` with the shortest distance.
+fn closest<C: IntoIterator<Item = Coord>>(distances: &DistanceMap, cells: C) -> Option<Coord> {
+    cells
+        .into_iter()
+        .filter_map(|x| Some((distances.distance(x)?, x)))
+        .min()
+        .map(|(_, x)| x)
+}
+
+pub fn main() -> crate::Exit {
+    run().into()
+}
+
+pub fn solve(input: crate::input::InputImpl, params: &crate::Params) -> crate::Result<String> {
+    let config = Config::from_params(params)?;
+    Ok(answer(input, &config)?.to_string())
+}
+
+fn run() -> crate::Result<()> {
+    let config = Config::from_params(&crate::params()?)?;
+    crate::main_impl(DAY, |input| answer(input, &config))
+}
+
+#[test]
+fn d12_example() {
+    assert_eq!(
+        answer(crate::input(DAY, true), &Config::default()).unwrap(),
+        Answer {
+            part1: Distance(Some(31)),
+            part2: Distance(Some(29)),
+        }
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{input::input_from_str, search::SearchStats};
+
+    use super::*;
+
+    fn map(lines: &[String]) -> HeightMap {
+        HeightMap::input(
+            lines.iter().map(|x| Ok(x.to_string())),
+            Markers::default(),
+            false,
+        )
+        .unwrap()
+    }
+
+    fn example() -> HeightMap {
+        HeightMap::input(crate::input(DAY, true), Markers::default(), false).unwrap()
+    }
+
+    fn forward(map: &HeightMap) -> (DistanceMap, SearchStats) {
+        search::bfs(&map.heights, map.starts.clone(), |from| {
+            map.steps(from, Navigation::Forward)
+        })
+    }
+
+    fn reverse(map: &HeightMap) -> (DistanceMap, SearchStats) {
+        search::bfs(&map.heights, map.ends.clone(), |from| {
+            map.steps(from, Navigation::Reverse)
+        })
+    }
+
+    fn astar_search(map: &HeightMap) -> (DistanceMap, SearchStats) {
+        search::astar(
+            &map.heights,
+            map.starts.clone(),
+            |from| map.steps(from, Navigation::Forward),
+            |from| map.estimate(from),
+            |from| map.ends.contains(&from),
+        )
+    }
+
+    #[test]
+    fn walled_off_end() {
+        let input = || input_from_str("Sabc\nabcd\ncddE");
+
+        let error = answer(input(), &Config::default()).unwrap_err();
+        assert!(error.is_semantic());
+        assert_eq!(error.to_string(), "No path from (0, 0) to (3, 2)");
+
+        let lenient = Config {
+            lenient: true,
+            ..Config::default()
+        };
+        assert_eq!(
+            answer(input(), &lenient).unwrap(),
+            Answer {
+                part1: Distance(None),
+                part2: Distance(None),
+            }
+        );
+        assert_eq!(format!("{:?}", Distance(None)), "unreachable");
+    }
+
+    #[test]
+    fn example_path() {
+        let map = example();
+        let (distances, _) = forward(&map);
+        let path = distances.path_to(map.ends[0]).unwrap();
+
+        assert_eq!(path.len(), 32);
+        assert_eq!(path.first(), Some(&map.starts[0]));
+        assert_eq!(path.last(), Some(&map.ends[0]));
+
+        let render = map.render_path(&path);
+        assert_eq!(render.matches(['^', 'v', '<', '>']).count(), 31);
+        assert_eq!(render.matches('E').count(), 1);
+    }
+
+    #[test]
+    fn path_arrows() {
+        let map = map(&["Sbc".to_string(), "Eed".to_string()]);
+        let (distances, _) = forward(&map);
+
+        let path = distances.path_to((1, 1)).unwrap();
+        assert_eq!(path, vec![(0, 0), (1, 0), (2, 0), (2, 1), (1, 1)]);
+        assert_eq!(map.render_path(&path), ">>v\n.*<\n");
+        assert_eq!(distances.path_to(map.ends[0]), None);
+    }
+
+    #[test]
+    fn diagonals() {
+        let map = map(&["SaE".to_string(), "aaa".to_string(), "aaa".to_string()]);
+        let mut diagonal = map.clone();
+        diagonal.rules.diagonals = true;
+
+        let (distances, _) = forward(&map);
+        let (diagonal_distances, _) = forward(&diagonal);
+
+        assert_eq!(distances.distance((2, 2)), Some(4));
+        assert_eq!(diagonal_distances.distance((2, 2)), Some(2));
+        let path = diagonal_distances.path_to((2, 2)).unwrap();
+        assert_eq!(diagonal.render_path(&path), "\\..\n.\\.\n..*\n");
+    }
+
+    #[test]
+    fn climb_rule() {
+        assert!(climbs(0, 1, 1, None));
+        assert!(!climbs(0, 2, 1, None));
+        assert!(climbs(25, 0, 1, None));
+        assert!(climbs(3, 3, 0, Some(0)));
+        assert!(climbs(5, 3, 1, Some(2)));
+        assert!(!climbs(5, 2, 1, Some(2)));
+    }
+
+    #[test]
+    fn max_climb() {
+        let config = Config {
+            rules: Rules {
+                max_climb: 2,
+                ..Rules::default()
+            },
+            ..Config::default()
+        };
+
+        assert_eq!(
+            answer(crate::input(DAY, true), &config).unwrap().part1,
+            Distance(Some(27))
+        );
+    }
+
+    #[test]
+    fn reverse_symmetry() {
+        let all_rules = [
+            Rules::default(),
+            Rules {
+                diagonals: true,
+                ..Rules::default()
+            },
+            Rules {
+                max_climb: 3,
+                max_drop: Some(2),
+                ..Rules::default()
+            },
+            Rules {
+                diagonals: true,
+                max_climb: 2,
+                max_drop: Some(1),
+            },
+        ];
+
+        for rules in all_rules {
+            let mut map = example();
+            map.rules = rules;
+
+            assert_eq!(
+                forward(&map).0.distance(map.ends[0]),
+                reverse(&map).0.distance(map.starts[0]),
+                "{rules:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn multiple_starts() {
+        let input = || input_from_str("Sbcdefghijklmnopqrstuvwxyz\nbbbbbbbbbbbbbbbbbbbbbbbbSE");
+        let with = |starts| Config {
+            markers: Markers {
+                starts,
+                ends: Policy::Strict,
+            },
+            ..Config::default()
+        };
+
+        assert!(answer(input(), &Config::default())
+            .unwrap_err()
+            .is_parsing());
+
+        let first = answer(input(), &with(Policy::First)).unwrap().part1;
+        let last = answer(input(), &with(Policy::Last)).unwrap().part1;
+        let multi = answer(input(), &with(Policy::Multi)).unwrap().part1;
+        assert_eq!(first, Distance(Some(26)));
+        assert_eq!(last, Distance(Some(47)));
+        assert_eq!(multi, first);
+
+        let map = HeightMap::input(
+            input(),
+            Markers {
+                starts: Policy::Multi,
+                ends: Policy::Strict,
+            },
+            false,
+        )
+        .unwrap();
+        assert_eq!(map.starts, vec![(0, 0), (24, 1)]);
+    }
+
+    #[test]
+    fn multiple_ends() {
+        let input = || input_from_str("SbcdefghijklmnopqrstuvwxyE\nabcdefghijklmnopqrstuvwxyE");
+        let config = Config {
+            markers: Markers {
+                starts: Policy::Strict,
+                ends: Policy::Multi,
+            },
+            ..Config::default()
+        };
+
+        assert!(answer(input(), &Config::default())
+            .unwrap_err()
+            .is_parsing());
+        assert_eq!(
+            answer(input(), &config).unwrap(),
+            Answer {
+                part1: Distance(Some(25)),
+                part2: Distance(Some(25)),
+            }
+        );
+        assert_eq!("multi".parse::<Policy>().unwrap(), Policy::Multi);
+        assert!("both".parse::<Policy>().is_err());
+    }
+
+    /// Deterministic pseudo random map with heights from `a` to `d`.
+    fn random_map(size: usize, mut seed: u64) -> HeightMap {
+        let mut lines = vec![];
+        for _ in 0..size {
+            let line = (0..size)
+                .map(|_| {
+                    seed = seed
+                        .wrapping_mul(6364136223846793005)
+                        .wrapping_add(1442695040888963407);
+                    (b'a' + (seed >> 61) as u8 % 4) as char
+                })
+                .collect::<String>();
+            lines.push(line);
+        }
+        lines[0].replace_range(0..1, "S");
+        lines[0].replace_range(size - 1..size, "E");
+
+        map(&lines)
+    }
+
+    #[test]
+    fn astar() {
+        let config = Config {
+            algorithm: Algorithm::AStar,
+            ..Config::default()
+        };
+        assert_eq!(
+            answer(crate::input(DAY, true), &config).unwrap(),
+            answer(crate::input(DAY, true), &Config::default()).unwrap(),
+        );
+
+        for seed in 0..5 {
+            let mut map = random_map(300, seed);
+            let goal = (299, 299);
+            map.ends = vec![goal];
+
+            assert_eq!(
+                forward(&map).0.distance(goal),
+                astar_search(&map).0.distance(goal),
+                "{seed}"
+            );
+        }
+    }
+
+    #[test]
+    fn lenient_grid() {
+        // Extra dead end columns, so losing them doesn't change the answer.
+        let mut lines = crate::input(DAY, true)
+            .map(|line| line.unwrap() + "aa")
+            .collect::<Vec<_>>();
+        lines[2].truncate(8);
+        let input = || lines.iter().map(|x| Ok(x.to_string()));
+
+        assert!(answer(input(), &Config::default())
+            .unwrap_err()
+            .is_parsing());
+
+        let config = Config {
+            lenient_grid: true,
+            ..Config::default()
+        };
+        assert_eq!(
+            answer(input(), &config).unwrap(),
+            Answer {
+                part1: Distance(Some(31)),
+                part2: Distance(Some(29)),
+            }
+        );
+
+        let map = HeightMap::input(input(), Markers::default(), true).unwrap();
+        assert_eq!(map.heights.width(), 10);
+        assert_eq!(map.padded, vec![PaddedLine { line: 3, width: 8 }]);
+        assert_eq!(
+            map.padded[0].to_string(),
+            "Line 3 has only 8 cells, padded with impassable cells"
+        );
+        assert_eq!(map.heights[(8, 2)], IMPASSABLE);
+        assert_eq!(map.steps((7, 2), Navigation::Reverse).count(), 3);
+        assert_eq!(map.heights.cells().filter(|&&x| x == 0).count(), 6 + 2 * 4);
+    }
+
+    #[test]
+    fn numeric() {
+        let input = || input_from_str("0,1,2,3\n9,9,9,4\n8,7,6,5");
+        let config = Config {
+            format: Format::Numeric {
+                start: (0, 0),
+                end: (0, 2),
+            },
+            ..Config::default()
+        };
+
+        let map = HeightMap::numeric(input(), (0, 0), (0, 2)).unwrap();
+        assert_eq!((map.heights.width(), map.heights.height()), (4, 3));
+        assert_eq!(map.heights[(1, 2)], 7);
+        assert_eq!(
+            answer(input(), &config).unwrap(),
+            Answer {
+                part1: Distance(Some(8)),
+                part2: Distance(Some(8)),
+            }
+        );
+
+        let steep = Config {
+            rules: Rules {
+                max_climb: 9,
+                ..Rules::default()
+            },
+            ..config
+        };
+        assert_eq!(answer(input(), &steep).unwrap().part1, Distance(Some(2)));
+
+        let params = crate::Params::default()
+            .with("format", Some("numeric"))
+            .with("start", Some("0,0"))
+            .with("end", Some("0,2"));
+        assert_eq!(Config::from_params(&params).unwrap().format, config.format);
+    }
+    #[test]
+    fn images() {
+        let map = example();
+        let (distances, _) = forward(&map);
+        let (start, end) = (map.starts[0], map.ends[0]);
+        let pixel = |image: &[u8], (x, y): Coord| image[image.len() - 40 + y * 8 + x];
+
+        let mut elevation = vec![];
+        pgm::write(&mut elevation, &map.elevation_image()).unwrap();
+        assert!(elevation.starts_with(b"P5\n8 5\n255\n"));
+        assert_eq!(elevation.len(), b"P5\n8 5\n255\n".len() + 40);
+        assert_eq!(pixel(&elevation, start), 0);
+        assert_eq!(pixel(&elevation, end), 255);
+        assert_eq!(pixel(&elevation, (3, 0)), (16 * 255 / 25) as u8);
+
+        let mut distance = vec![];
+        pgm::write(&mut distance, &distance_image(&distances)).unwrap();
+        let max = distances
+            .distances()
+            .cells()
+            .flatten()
+            .max()
+            .copied()
+            .unwrap();
+        assert!(distance.starts_with(b"P5\n8 5\n255\n"));
+        assert_eq!(distance.len(), elevation.len());
+        assert_eq!(pixel(&distance, start), 1);
+        assert_eq!(pixel(&distance, end) as usize, 1 + 31 * 254 / max);
+
+        let mut walled = map.clone();
+        walled.heights[(1, 0)] = 25;
+        walled.heights[(0, 1)] = 25;
+        let (distances, _) = forward(&walled);
+        assert_eq!(distance_image(&distances)[end], 0);
+    }
+
+    #[test]
+    fn numeric_errors() {
+        let input = |lines: &[&str]| {
+            lines
+                .iter()
+                .map(|x| Ok(x.to_string()))
+                .collect::<Vec<_>>()
+                .into_iter()
+        };
+
+        let error = HeightMap::numeric(input(&["0,1", "2,256"]), (0, 0), (1, 1)).unwrap_err();
+        assert!(matches!(error, ParseError::BadElevation(2, _, _)));
+        let error = HeightMap::numeric(input(&["0,1", "2,-1"]), (0, 0), (1, 1)).unwrap_err();
+        assert!(matches!(error, ParseError::BadElevation(2, _, _)));
+        let error = HeightMap::numeric(input(&["0,1", "2,3"]), (0, 0), (2, 1)).unwrap_err();
+        assert!(matches!(error, ParseError::OutsideMap("End", (2, 1))));
+        let error = HeightMap::numeric(input(&[]), (0, 0), (0, 0)).unwrap_err();
+        assert!(matches!(error, ParseError::EmptyMap));
+        let error = HeightMap::input(input(&[]), Markers::default(), false).unwrap_err();
+        assert!(matches!(error, ParseError::EmptyMap));
+        assert!(answer(input(&[]), &Config::default())
+            .unwrap_err()
+            .is_parsing());
+
+        let missing_start = crate::Params::default()
+            .with("format", Some("numeric"))
+            .with("end", Some("0,2"));
+        let error = Config::from_params(&missing_start).unwrap_err();
+        assert!(error.to_string().contains("\"start\""));
+
+        let missing_end = crate::Params::default()
+            .with("format", Some("numeric"))
+            .with("start", Some("0,2"));
+        assert!(Config::from_params(&missing_end).is_err());
+
+        let bad_coord = missing_end.with("end", Some("1;2"));
+        assert!(Config::from_params(&bad_coord).is_err());
+    }
+}
@@ -0,0 +1,748 @@
+use crate::{
+    input::{Input, InputError},
+    nested::{self, Value as Packet, MAX_DEPTH},
+    Answer,
+};
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+pub const DAY: u32 = 13;
+
+#[derive(thiserror::Error, Debug)]
+enum ParseError {
+    #[error("{0}")]
+    Input(#[from] InputError),
+    #[error("{0}")]
+    Packet(#[from] nested::ParseError),
+    #[cfg(feature = "serde")]
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+    #[cfg(feature = "serde")]
+    #[error("{0}")]
+    JsonValue(#[from] nested::JsonError),
+    #[cfg(feature = "serde")]
+    #[error("More than {0} nested lists")]
+    JsonTooDeep(usize),
+}
+impl ParseError {
+    /// Byte offset of the error within the line, if known.
+    fn offset(&self) -> Option<usize> {
+        match self {
+            ParseError::Packet(e) => e.offset(),
+            #[cfg(feature = "serde")]
+            ParseError::Json(e) => Some(e.column().saturating_sub(1)),
+            _ => None,
+        }
+    }
+}
+
+/// A [`ParseError`] along with the line it happened on. The alternate form
+/// (`{:#}`) also shows the line with a caret under the offending column.
+#[derive(Debug)]
+struct LineError {
+    /// Starting from 1.
+    line: usize,
+    text: String,
+    error: ParseError,
+}
+impl LineError {
+    fn new(idx: usize, text: String, error: ParseError) -> Self {
+        LineError {
+            line: idx + 1,
+            text,
+            error,
+        }
+    }
+}
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.error)?;
+        if let (true, Some(offset)) = (f.alternate(), self.error.offset()) {
+            write!(f, "\n{}\n{:>width$}", self.text, "^", width = offset + 1)?;
+        }
+
+        Ok(())
+    }
+}
+impl std::error::Error for LineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+impl From<LineError> for crate::Error {
+    fn from(value: LineError) -> Self {
+        crate::Error::Parsing(value.into())
+    }
+}
+
+/// The input read as pairs of packets separated by blank lines.
+struct Pairs<I> {
+    lines: std::iter::Enumerate<I>,
+    parser: Parser,
+    max_depth: usize,
+    /// Requires exactly one blank line between pairs and none within them.
+    strict_layout: bool,
+    /// Ignores a last packet without a pair, with a warning.
+    lenient: bool,
+    /// Packets read so far.
+    packets: usize,
+    warnings: Vec<PairError>,
+}
+impl<I: Input> Pairs<I> {
+    fn new(input: I, config: &Config) -> Self {
+        Pairs {
+            lines: input.enumerate(),
+            parser: config.parser,
+            max_depth: config.max_depth,
+            strict_layout: config.strict_layout,
+            lenient: config.lenient,
+            packets: 0,
+            warnings: vec![],
+        }
+    }
+
+    /// The next packet, checking that it comes after `expected` blank lines
+    /// under the strict layout.
+    fn packet(&mut self, expected: usize) -> Option<Result<Packet, PairError>> {
+        let mut found = 0;
+        let (idx, line) = loop {
+            match self.lines.next()? {
+                (_, Ok(line)) if line.is_empty() => found += 1,
+                (idx, Ok(line)) => break (idx, line),
+                (idx, Err(e)) => {
+                    return Some(Err(LineError::new(idx, String::new(), e.into()).into()))
+                }
+            }
+        };
+        self.packets += 1;
+
+        if self.strict_layout && found != expected {
+            return Some(Err(PairError::Layout {
+                line: idx + 1,
+                expected,
+                found,
+            }));
+        }
+
+        Some(
+            self.parser
+                .parse(&line, self.max_depth)
+                .map_err(|e| LineError::new(idx, line, e).into()),
+        )
+    }
+}
+impl<I: Input> Iterator for Pairs<I> {
+    type Item = Result<(Packet, Packet), PairError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let separator = usize::from(self.packets > 0);
+        let left = match self.packet(separator)? {
+            Ok(left) => left,
+            Err(e) => return Some(Err(e)),
+        };
+
+        let right = match self.packet(0) {
+            Some(Ok(right)) => right,
+            Some(Err(e)) => return Some(Err(e)),
+            None => {
+                let unpaired = PairError::UnpairedPacket {
+                    index: self.packets,
+                };
+                if !self.lenient {
+                    return Some(Err(unpaired));
+                }
+
+                self.warnings.push(unpaired);
+                return None;
+            }
+        };
+
+        Some(Ok((left, right)))
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+enum PairError {
+    #[error("{0}")]
+    Line(#[from] LineError),
+    #[error("Packet {index} has no pair")]
+    UnpairedPacket {
+        /// Starting from 1.
+        index: usize,
+    },
+    #[error("Line {line}: expected {expected} blank lines before it, found {found}")]
+    Layout {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+impl From<PairError> for crate::Error {
+    fn from(value: PairError) -> Self {
+        match value {
+            PairError::Line(e) => e.into(),
+            e => crate::Error::Semantic(e.into()),
+        }
+    }
+}
+
+/// The full sort of `--param full-sort` placed the dividers elsewhere than
+/// counting did.
+#[derive(thiserror::Error, Debug)]
+#[error("Sorting puts the dividers at {sorted:?}, counting at {counted:?}")]
+struct SortMismatch {
+    sorted: Vec<u32>,
+    counted: Vec<u32>,
+}
+impl From<SortMismatch> for crate::Error {
+    fn from(value: SortMismatch) -> Self {
+        crate::Error::Semantic(value.into())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Parser {
+    /// The packet parser of this day.
+    #[default]
+    Packet,
+    /// The packet parser without any whitespace, as the puzzle writes them.
+    Strict,
+    /// Lines read as JSON, to check the packet parser against. The depth is
+    /// checked once parsed, and serde_json refuses more than 128 nested
+    /// lists on its own, so only a lower maximum depth has an effect.
+    #[cfg(feature = "serde")]
+    Json,
+}
+impl Parser {
+    fn parse(self, line: &str, max_depth: usize) -> Result<Packet, ParseError> {
+        match self {
+            Parser::Packet => Ok(Packet::parse(line, true, max_depth)?),
+            Parser::Strict => Ok(Packet::parse(line, false, max_depth)?),
+            #[cfg(feature = "serde")]
+            Parser::Json => {
+                let value: serde_json::Value = serde_json::from_str(line)?;
+                if json_depth(&value) > max_depth {
+                    return Err(ParseError::JsonTooDeep(max_depth));
+                }
+                Ok(Packet::try_from(value)?)
+            }
+        }
+    }
+}
+impl FromStr for Parser {
+    type Err = UnknownParser;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "packet" => Ok(Parser::Packet),
+            "strict" => Ok(Parser::Strict),
+            #[cfg(feature = "serde")]
+            "json" => Ok(Parser::Json),
+            other => Err(UnknownParser(other.to_string())),
+        }
+    }
+}
+
+/// Arrays nested in `value`, itself included. Recursive, as serde_json keeps
+/// the depth low.
+#[cfg(feature = "serde")]
+fn json_depth(value: &serde_json::Value) -> usize {
+    match value {
+        serde_json::Value::Array(items) => 1 + items.iter().map(json_depth).max().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    error("Unknown parser {0:?}, expected packet, strict or json")
+)]
+#[cfg_attr(
+    not(feature = "serde"),
+    error("Unknown parser {0:?}, expected packet or strict (json needs the serde feature)")
+)]
+struct UnknownParser(String);
+
+/// Packets added for part 2, whose positions once sorted make the decoder
+/// key. Packets equal to a divider are sorted after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Dividers(Vec<Packet>);
+impl Default for Dividers {
+    fn default() -> Self {
+        Dividers(vec![Packet::from([[2]]), Packet::from([[6]])])
+    }
+}
+impl FromStr for Dividers {
+    type Err = nested::ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Dividers(
+            s.split(';')
+                .map(|x| x.trim().parse())
+                .collect::<Result<_, _>>()?,
+        ))
+    }
+}
+impl Dividers {
+    /// Counts positions, and with `neighbors` also keeps the packets closest
+    /// to each divider.
+    fn counter(&self, neighbors: bool) -> DividerCounter<'_> {
+        // Equal dividers keep their order, as a stable sort would.
+        let positions = self
+            .0
+            .iter()
+            .enumerate()
+            .map(|(i, divider)| {
+                let before = self.0.iter().enumerate().filter(|&(j, other)| {
+                    match other.cmp_promoting(divider) {
+                        Ordering::Less => true,
+                        Ordering::Equal => j < i,
+                        Ordering::Greater => false,
+                    }
+                });
+                before.count() as u32 + 1
+            })
+            .collect();
+
+        DividerCounter {
+            dividers: &self.0,
+            positions,
+            neighbors: neighbors.then(|| vec![Neighbors::default(); self.0.len()]),
+        }
+    }
+
+    /// Sorts `packets` along with the dividers, returning the sorted packets
+    /// and the position of each divider.
+    fn sort(&self, packets: Vec<Packet>) -> (Vec<Packet>, Vec<u32>) {
+        let mut sorted = self
+            .0
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(i, divider)| (divider, Some(i)))
+            .chain(packets.into_iter().map(|packet| (packet, None)))
+            .collect::<Vec<_>>();
+        sorted.sort_by(|(a, _), (b, _)| a.cmp_promoting(b));
+
+        let mut positions = vec![0; self.0.len()];
+        for (position, (_, divider)) in sorted.iter().enumerate() {
+            if let Some(divider) = divider {
+                positions[*divider] = position as u32 + 1;
+            }
+        }
+
+        (sorted.into_iter().map(|(x, _)| x).collect(), positions)
+    }
+}
+
+/// Positions of the dividers found by counting the packets sorted before
+/// them, without sorting.
+struct DividerCounter<'a> {
+    dividers: &'a [Packet],
+    positions: Vec<u32>,
+    neighbors: Option<Vec<Neighbors>>,
+}
+impl DividerCounter<'_> {
+    fn add(&mut self, packet: &Packet) {
+        for (i, divider) in self.dividers.iter().enumerate() {
+            let ordering = packet.cmp_promoting(divider);
+            if ordering.is_lt() {
+                self.positions[i] += 1;
+            }
+
+            if let Some(neighbors) = &mut self.neighbors {
+                neighbors[i].add(packet, ordering);
+            }
+        }
+    }
+}
+
+/// The packets right before and after a divider once sorted, leaving the
+/// other dividers out.
+#[derive(Clone, Debug, Default)]
+struct Neighbors {
+    below: Option<Packet>,
+    above: Option<Packet>,
+}
+impl Neighbors {
+    /// Takes `packet` into account, given how it compares to the divider.
+    /// Equal packets are sorted after the divider, and after the packets
+    /// they equal that came before them.
+    fn add(&mut self, packet: &Packet, ordering: Ordering) {
+        let closer = |closest: &Option<Packet>| match (ordering, closest) {
+            (_, None) => true,
+            (Ordering::Less, Some(below)) => packet.cmp_promoting(below).is_ge(),
+            (_, Some(above)) => packet.cmp_promoting(above).is_lt(),
+        };
+
+        let closest = match ordering {
+            Ordering::Less => &mut self.below,
+            _ => &mut self.above,
+        };
+        if closer(closest) {
+            *closest = Some(packet.clone());
+        }
+    }
+}
+impl fmt::Display for Neighbors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |packet: &Option<Packet>| match packet {
+            Some(packet) => packet.to_string(),
+            None => "nothing".to_string(),
+        };
+
+        write!(f, "between {} and {}", show(&self.below), show(&self.above))
+    }
+}
+
+#[derive(Debug)]
+struct Config {
+    parser: Parser,
+    dividers: Dividers,
+    /// Sorts every packet to print where the dividers end up.
+    full_sort: bool,
+    verbose: bool,
+    max_depth: usize,
+    strict_layout: bool,
+    lenient: bool,
+}
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            parser: Default::default(),
+            dividers: Default::default(),
+            full_sort: false,
+            verbose: false,
+            max_depth: MAX_DEPTH,
+            strict_layout: false,
+            lenient: false,
+        }
+    }
+}
+impl Config {
+    fn from_params(params: &crate::Params) -> crate::Result<Self> {
+        Ok(Config {
+            parser: params.parse("parser")?.unwrap_or_default(),
+            dividers: params.parse("dividers")?.unwrap_or_default(),
+            full_sort: params.flag("full-sort"),
+            verbose: params.verbose(),
+            max_depth: params.parse("max-depth")?.unwrap_or(MAX_DEPTH),
+            strict_layout: params.flag("strict-layout"),
+            lenient: params.flag("lenient"),
+        })
+    }
+}
+
+fn answer<I: Input>(input: I, config: &Config) -> crate::Result<Answer> {
+    let mut pairs = Pairs::new(input, config);
+
+    let mut counter = config.dividers.counter(config.verbose);
+    let mut all = vec![];
+
+    let mut ordered = 0;
+    for (idx, pair) in pairs.by_ref().enumerate() {
+        let idx = idx as u32 + 1;
+        let (left, right) = pair.inspect_err(|e| {
+            if let (true, PairError::Line(e)) = (config.verbose, e) {
+                eprintln!("{e:#}");
+            }
+        })?;
+
+        if left.cmp_promoting(&right) == Ordering::Less {
+            ordered += idx;
+        }
+
+        counter.add(&left);
+        counter.add(&right);
+        if config.full_sort {
+            all.extend([left, right]);
+        }
+    }
+
+    for warning in &pairs.warnings {
+        eprintln!("Warning: {warning}");
+    }
+
+    if let Some(neighbors) = &counter.neighbors {
+        for ((divider, position), neighbors) in config
+            .dividers
+            .0
+            .iter()
+            .zip(&counter.positions)
+            .zip(neighbors)
+        {
+            eprintln!("Divider {divider} at {position}, {neighbors}");
+        }
+    }
+
+    if config.full_sort {
+        let (sorted, positions) = config.dividers.sort(all);
+        for (divider, position) in config.dividers.0.iter().zip(&positions) {
+            eprintln!("Divider {divider} at {position}");
+        }
+        for (position, packet) in sorted.iter().enumerate().take(10) {
+            eprintln!("{:>4}: {packet}", position + 1);
+        }
+        if positions != counter.positions {
+            return Err(SortMismatch {
+                sorted: positions,
+                counted: counter.positions,
+            }
+            .into());
+        }
+    }
+
+    let decoder = counter.positions.iter().product();
+
+    Ok(Answer {
+        part1: ordered,
+        part2: decoder,
+    })
+}
+
+pub fn main() -> crate::Exit {
+    run().into()
+}
+
+pub fn solve(input: crate::input::InputImpl, params: &crate::Params) -> crate::Result<String> {
+    let config = Config::from_params(params)?;
+    Ok(answer(input, &config)?.to_string())
+}
+
+fn run() -> crate::Result<()> {
+    let config = Config::from_params(&crate::params()?)?;
+    crate::main_impl(DAY, |input| answer(input, &config))
+}
+
+#[test]
+fn d13_example() {
+    assert_eq!(
+        answer(crate::input(DAY, true), &Config::default()).unwrap(),
+        Answer {
+            part1: 13,
+            part2: 140,
+        }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::{input_from_lines, input_from_str};
+
+    use super::*;
+
+    #[test]
+    fn dividers() {
+        let config = Config {
+            dividers: "[[1]]; [[9]]".parse().unwrap(),
+            full_sort: true,
+            ..Config::default()
+        };
+        assert_eq!(
+            answer(crate::input(DAY, true), &config).unwrap().part2,
+            4 * 17
+        );
+
+        let config = Config {
+            full_sort: true,
+            ..Config::default()
+        };
+        assert_eq!(answer(crate::input(DAY, true), &config).unwrap().part2, 140);
+
+        assert!("[[2]];[6".parse::<Dividers>().is_err());
+    }
+
+    #[test]
+    fn dividers_sort_and_count() {
+        let mut seed = 1967;
+        for _ in 0..200 {
+            // Shallow packets with few numbers, so that ties are common.
+            let dividers = Dividers(
+                (0..4)
+                    .map(|_| nested::random_value(&mut seed, 2, 2))
+                    .collect(),
+            );
+            let packets = (0..30)
+                .map(|_| nested::random_value(&mut seed, 3, 3))
+                .collect::<Vec<_>>();
+
+            let mut counter = dividers.counter(false);
+            for packet in &packets {
+                counter.add(packet);
+            }
+            let (sorted, positions) = dividers.sort(packets);
+
+            assert_eq!(positions, counter.positions, "{:?}", dividers.0);
+            assert!(sorted.windows(2).all(|x| x[0].cmp_promoting(&x[1]).is_le()));
+        }
+    }
+
+    #[test]
+    fn neighbors() {
+        let dividers = Dividers::default();
+        let mut counter = dividers.counter(true);
+        let mut packets = vec![];
+        for pair in Pairs::new(crate::input(DAY, true), &Config::default()) {
+            let (left, right) = pair.unwrap();
+            counter.add(&left);
+            counter.add(&right);
+            packets.extend([left, right]);
+        }
+
+        let (sorted, positions) = dividers.sort(packets);
+        let neighbors = counter.neighbors.unwrap();
+        for (position, neighbors) in positions.into_iter().zip(&neighbors) {
+            let position = position as usize - 1;
+            assert_eq!(neighbors.below.as_ref(), Some(&sorted[position - 1]));
+            assert_eq!(neighbors.above.as_ref(), Some(&sorted[position + 1]));
+        }
+        assert_eq!(neighbors[0].to_string(), "between [[1],4] and [3]");
+        assert_eq!(
+            neighbors[1].to_string(),
+            "between [[4,4],4,4,4] and [7,7,7]"
+        );
+
+        let mut counter = dividers.counter(true);
+        let packet = |s: &str| s.parse::<Packet>().unwrap();
+        counter.add(&packet("[9]"));
+        let neighbors = counter.neighbors.as_ref().unwrap();
+        assert_eq!(neighbors[0].to_string(), "between nothing and [9]");
+        assert_eq!(neighbors[1].to_string(), "between nothing and [9]");
+
+        for line in ["[7]", "[[2]]", "[1]", "[4]", "[2]"] {
+            counter.add(&packet(line));
+        }
+        let neighbors = counter.neighbors.as_ref().unwrap();
+        // `[2]` equals `[[2]]` but comes later, so it is sorted after it.
+        assert_eq!(neighbors[0].to_string(), "between [1] and [[2]]");
+        assert_eq!(neighbors[1].to_string(), "between [4] and [7]");
+        assert_eq!(counter.positions, [2, 6]);
+    }
+
+    #[test]
+    fn error_columns() {
+        let input = input_from_str("[1]\n\n[[2],\n[3]");
+        let error = Pairs::new(input.into_iter(), &Config::default())
+            .find_map(Result::err)
+            .unwrap();
+        let PairError::Line(error) = error else {
+            panic!("{error}")
+        };
+        assert_eq!(error.line, 3);
+        assert_eq!(
+            format!("{error:#}"),
+            "Line 3: Line abruptly ends within a list at column 6\n[[2],\n     ^"
+        );
+    }
+
+    #[test]
+    fn pairs() {
+        let example = Pairs::new(crate::input(DAY, true), &Config::default());
+        assert_eq!(example.map(Result::unwrap).count(), 8);
+
+        let strict = Config {
+            strict_layout: true,
+            ..Config::default()
+        };
+        let lenient = Config {
+            lenient: true,
+            ..Config::default()
+        };
+
+        let odd = ["[1]", "[2]", "", "[3]", "[4]", "", "[5]"];
+        let errors = Pairs::new(input_from_lines(&odd), &Config::default())
+            .filter_map(Result::err)
+            .collect::<Vec<_>>();
+        assert!(matches!(
+            errors[..],
+            [PairError::UnpairedPacket { index: 5 }]
+        ));
+        assert_eq!(errors[0].to_string(), "Packet 5 has no pair");
+        assert!(answer(input_from_lines(&odd), &Config::default()).is_err());
+
+        let mut pairs = Pairs::new(input_from_lines(&odd), &lenient);
+        assert_eq!(pairs.by_ref().map(Result::unwrap).count(), 2);
+        assert!(matches!(
+            pairs.warnings[..],
+            [PairError::UnpairedPacket { index: 5 }]
+        ));
+        assert_eq!(
+            answer(input_from_lines(&odd), &lenient).unwrap().part1,
+            1 + 2
+        );
+
+        let squashed = ["[1]", "[2]", "", "[3]", "[4]", "[5]", "[6]", ""];
+        assert_eq!(
+            Pairs::new(input_from_lines(&squashed), &Config::default())
+                .map(Result::unwrap)
+                .count(),
+            3
+        );
+        let error = Pairs::new(input_from_lines(&squashed), &strict).find_map(Result::err);
+        assert!(matches!(
+            error,
+            Some(PairError::Layout {
+                line: 6,
+                expected: 1,
+                found: 0
+            })
+        ));
+
+        let split = ["[1]", "", "[2]"];
+        assert!(Pairs::new(input_from_lines(&split), &Config::default()).all(|x| x.is_ok()));
+        assert!(Pairs::new(input_from_lines(&split), &strict).any(|x| x.is_err()));
+        assert!(Pairs::new(crate::input(DAY, true), &strict).all(|x| x.is_ok()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_parser() {
+        for line in crate::input(DAY, true).map(Result::unwrap) {
+            if line.is_empty() {
+                continue;
+            }
+
+            assert_eq!(
+                Parser::Json.parse(&line, MAX_DEPTH).unwrap(),
+                Parser::Packet.parse(&line, MAX_DEPTH).unwrap()
+            );
+        }
+
+        assert_eq!(
+            answer(
+                crate::input(DAY, true),
+                &Config {
+                    parser: Parser::Json,
+                    ..Config::default()
+                }
+            )
+            .unwrap(),
+            answer(crate::input(DAY, true), &Config::default()).unwrap()
+        );
+
+        for invalid in ["[1.5]", "[true]", "[\"1\"]", "{}", "[null]", "[1"] {
+            assert!(Parser::Json.parse(invalid, MAX_DEPTH).is_err(), "{invalid}");
+        }
+
+        assert!(Parser::Json.parse("[[[1]], 2]", 3).is_ok());
+        assert!(matches!(
+            Parser::Json.parse("[[[1]], 2]", 2),
+            Err(ParseError::JsonTooDeep(2))
+        ));
+        assert!(matches!(
+            Parser::Packet.parse("[[[1]], 2]", 2),
+            Err(ParseError::Packet(nested::ParseError::TooDeep {
+                depth: 2,
+                ..
+            }))
+        ));
+
+        // Past the recursion limit of serde_json, even below `max_depth`.
+        let deep = format!("{}{}", "[".repeat(200), "]".repeat(200));
+        assert!(Parser::Packet.parse(&deep, MAX_DEPTH).is_ok());
+        assert!(matches!(
+            Parser::Json.parse(&deep, MAX_DEPTH),
+            Err(ParseError::Json(_))
+        ));
+    }
+}
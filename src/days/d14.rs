@@ -0,0 +1,1632 @@
+use crate::{
+    grid::Grid,
+    input::{Input, InputError},
+    pgm, Answer,
+};
+use itertools::Itertools;
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+    num::ParseIntError,
+    ops::Range,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+pub const DAY: u32 = 14;
+const START: Coord = Coord(0, 500);
+
+/// What stops sand once it passes the lowest rock.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Boundary {
+    /// An endless floor this many lines below the lowest rock.
+    Floor(i64),
+    /// Nothing, the sand falls forever.
+    #[default]
+    Abyss,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct MapConfig {
+    boundary: Boundary,
+    /// Most bytes a [`DenseMap`] may take.
+    budget: u64,
+}
+impl Default for MapConfig {
+    fn default() -> Self {
+        MapConfig::PART1
+    }
+}
+impl MapConfig {
+    const BUDGET: u64 = 1 << 30;
+    const PART1: MapConfig = MapConfig {
+        boundary: Boundary::Abyss,
+        budget: MapConfig::BUDGET,
+    };
+    const PART2: MapConfig = MapConfig {
+        boundary: Boundary::Floor(2),
+        budget: MapConfig::BUDGET,
+    };
+
+    /// Line of the floor under rocks going down to `max`.
+    fn floor(self, max: Coord) -> Option<i64> {
+        match self.boundary {
+            Boundary::Floor(below) => Some(max.0.saturating_add(below)),
+            Boundary::Abyss => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Cell {
+    #[default]
+    Air,
+    Rock,
+    Sand,
+}
+impl Cell {
+    fn symbol(self) -> char {
+        match self {
+            Cell::Air => '.',
+            Cell::Rock => '#',
+            Cell::Sand => 'o',
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Cell::Air => "air",
+            Cell::Rock => "rock",
+            Cell::Sand => "sand",
+        }
+    }
+
+    /// Gray level in a dumped image.
+    fn shade(self) -> u8 {
+        match self {
+            Cell::Air => 255,
+            Cell::Rock => 0,
+            Cell::Sand => 160,
+        }
+    }
+}
+
+/// What became of a grain of sand.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Outcome {
+    Settled(Coord),
+    /// Left the map from this cell.
+    FellIntoAbyss(Coord),
+    SourceBlocked,
+}
+
+trait Map {
+    fn sources(&self) -> &[Coord];
+
+    /// Drops a grain of sand from the last position of `path`, which is
+    /// the way down from its source: right after a grain settles, the next
+    /// one goes through the same cells until one above where the previous one
+    /// came to rest.
+    fn sand_along(&mut self, path: &mut Vec<Coord>) -> Outcome {
+        'fall: while let Some(&sand) = path.last() {
+            if self.empty(sand) != Some(true) {
+                path.pop();
+                continue;
+            }
+
+            for delta in [Coord(1, 0), Coord(1, -1), Coord(1, 1)] {
+                let to = Coord(sand.0 + delta.0, sand.1 + delta.1);
+                match self.empty(to) {
+                    None => return Outcome::FellIntoAbyss(sand),
+                    Some(true) => {
+                        path.push(to);
+                        continue 'fall;
+                    }
+                    Some(false) => (),
+                }
+            }
+
+            self.set(sand);
+            path.pop();
+            return Outcome::Settled(sand);
+        }
+
+        Outcome::SourceBlocked
+    }
+
+    /// The cells around the rocks and the sources, `margin` cells further on
+    /// each side, as in the puzzle statement.
+    fn render(&self, margin: i64) -> String {
+        let (min, max) = self.bounds();
+
+        (min.0 - margin..=max.0 + margin)
+            .map(|y| {
+                (min.1 - margin..=max.1 + margin)
+                    .map(|x| match self.cell(Coord(y, x)).unwrap_or_default() {
+                        Cell::Air if self.sources().contains(&Coord(y, x)) => '+',
+                        cell => cell.symbol(),
+                    })
+                    .collect::<String>()
+            })
+            .join("\n")
+    }
+
+    /// Whether sand can go to `coord`, `None` if it falls into the abyss.
+    fn empty(&self, coord: Coord) -> Option<bool> {
+        Some(self.cell(coord)? == Cell::Air)
+    }
+
+    /// `None` if `coord` is in the abyss. Without a floor, that is anywhere
+    /// outside of [`Map::bounds`]: sand leaving them never meets a rock again,
+    /// so both maps report it falling from the same cell.
+    fn cell(&self, coord: Coord) -> Option<Cell>;
+    /// Puts sand at `coord`.
+    fn set(&mut self, coord: Coord);
+    /// Corners of the area taken by the rocks and the sources.
+    fn bounds(&self) -> (Coord, Coord);
+    /// Rocks and sand, without the floor.
+    fn occupied(&self) -> BTreeMap<Coord, Cell>;
+}
+
+#[derive(Clone, Debug)]
+struct SparseMap {
+    cells: BTreeMap<Coord, Cell>,
+    min: Coord,
+    max: Coord,
+    sources: Vec<Coord>,
+    config: MapConfig,
+}
+impl Default for SparseMap {
+    fn default() -> Self {
+        Self {
+            cells: Default::default(),
+            min: START,
+            max: START,
+            sources: vec![START],
+            config: Default::default(),
+        }
+    }
+}
+impl SparseMap {
+    /// The rocks of `self` with sand falling from `sources` instead.
+    fn with_sources(self, sources: Vec<Coord>) -> Self {
+        let coords = self.cells.keys().chain(&sources).copied();
+        let (min, max) = Coord::bounds(coords).unwrap_or((START, START));
+
+        SparseMap {
+            min,
+            max,
+            sources,
+            ..self
+        }
+    }
+
+    fn with_config(&self, config: MapConfig) -> Self {
+        SparseMap {
+            config,
+            ..self.clone()
+        }
+    }
+}
+impl FromIterator<Movement> for SparseMap {
+    fn from_iter<T: IntoIterator<Item = Movement>>(iter: T) -> Self {
+        let mut map = SparseMap::default();
+
+        for mov in iter {
+            for coord in mov {
+                map.min.0 = map.min.0.min(coord.0);
+                map.min.1 = map.min.1.min(coord.1);
+                map.max.0 = map.max.0.max(coord.0);
+                map.max.1 = map.max.1.max(coord.1);
+                map.cells.insert(coord, Cell::Rock);
+            }
+        }
+
+        map
+    }
+}
+impl Map for SparseMap {
+    fn sources(&self) -> &[Coord] {
+        &self.sources
+    }
+
+    fn cell(&self, coord: Coord) -> Option<Cell> {
+        match self.config.floor(self.max) {
+            Some(floor) if coord.0 >= floor => return Some(Cell::Rock),
+            None if !(self.min.0..=self.max.0).contains(&coord.0) => return None,
+            None if !(self.min.1..=self.max.1).contains(&coord.1) => return None,
+            _ => (),
+        }
+
+        Some(self.cells.get(&coord).copied().unwrap_or_default())
+    }
+
+    fn set(&mut self, coord: Coord) {
+        self.cells.insert(coord, Cell::Sand);
+    }
+
+    fn bounds(&self) -> (Coord, Coord) {
+        (self.min, self.max)
+    }
+
+    fn occupied(&self) -> BTreeMap<Coord, Cell> {
+        self.cells.clone()
+    }
+}
+
+/// Width and height of the cells from `min` to `max`, both included, unless
+/// at `bytes` bytes each they would take more than `budget` bytes.
+fn area(min: Coord, max: Coord, bytes: u64, budget: u64) -> Result<(u64, u64), SizeError> {
+    let length = |min: i64, max: i64| {
+        max.checked_sub(min)
+            .and_then(|length| length.checked_add(1))
+            .and_then(|length| u64::try_from(length).ok())
+            .ok_or(SizeError::Overflow)
+    };
+    let width = length(min.1, max.1)?;
+    let height = length(min.0, max.0)?;
+    width
+        .checked_mul(height)
+        .and_then(|cells| cells.checked_mul(bytes))
+        .filter(|&total| total <= budget)
+        .ok_or(SizeError::OverBudget {
+            width,
+            height,
+            budget,
+        })?;
+
+    Ok((width, height))
+}
+
+#[derive(Clone)]
+struct DenseMap {
+    width: usize,
+    /// Coordinate of the first cell, subtracted from every coordinate to
+    /// index `cells`.
+    origin: Coord,
+    sources: Vec<Coord>,
+    bounds: (Coord, Coord),
+    /// Line of the floor, which is filled with rock.
+    floor: Option<i64>,
+    cells: Vec<Cell>,
+}
+impl std::fmt::Debug for DenseMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cells = self
+            .lines()
+            .map(|l| l.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>();
+        f.debug_struct("DenseMap")
+            .field("width", &self.width)
+            .field("origin", &self.origin)
+            .field("sources", &self.sources)
+            .field("bounds", &self.bounds)
+            .field("floor", &self.floor)
+            .field("cells", &cells)
+            .finish()
+    }
+}
+impl DenseMap {
+    /// The map of `rocks` under `config`, drawn right into it. Rock paths are
+    /// made of straight or diagonal segments, so their ends are enough to
+    /// know the area they take.
+    fn from_rocks(
+        rocks: &[Movement],
+        sources: Vec<Coord>,
+        config: MapConfig,
+    ) -> Result<Self, SizeError> {
+        // A lone point draws nothing.
+        let ends = rocks.iter().filter(|rock| rock.0.len() > 1);
+        let coords = ends.flat_map(|rock| &rock.0).chain(&sources).copied();
+        let bounds = Coord::bounds(coords).unwrap_or((START, START));
+
+        let mut dense = DenseMap::empty(bounds, sources, config)?;
+        for coord in rocks.iter().flatten() {
+            *dense.cell_mut(coord) = Cell::Rock;
+        }
+
+        Ok(dense)
+    }
+
+    /// A map without rocks for rocks and sources within `bounds`. Without a
+    /// floor it only covers `bounds`, anything else is the abyss. With a
+    /// floor it is wide enough that sand piling from the sources never
+    /// reaches its ends.
+    fn empty(
+        bounds: (Coord, Coord),
+        sources: Vec<Coord>,
+        config: MapConfig,
+    ) -> Result<Self, SizeError> {
+        let (min, max) = bounds;
+        let Some(floor) = config.floor(max) else {
+            return DenseMap::window(min, max, bounds, sources, config.budget);
+        };
+
+        let reach = |source: &Coord| {
+            floor
+                .checked_sub(source.0)
+                .and_then(|reach| reach.checked_add(1))
+                .ok_or(SizeError::Overflow)
+        };
+        let (mut left, mut right) = (min.1, max.1);
+        for source in &sources {
+            let reach = reach(source)?;
+            left = left.min(source.1.checked_sub(reach).ok_or(SizeError::Overflow)?);
+            right = right.max(source.1.checked_add(reach).ok_or(SizeError::Overflow)?);
+        }
+
+        let mut dense = DenseMap::window(
+            Coord(min.0, left),
+            Coord(floor, right),
+            bounds,
+            sources,
+            config.budget,
+        )?;
+        dense.line_mut((floor - min.0) as usize).fill(Cell::Rock);
+        dense.floor = Some(floor);
+        Ok(dense)
+    }
+
+    /// Air from `min` to `max`, both included, unless it would take more
+    /// than `budget` bytes.
+    fn window(
+        min: Coord,
+        max: Coord,
+        bounds: (Coord, Coord),
+        sources: Vec<Coord>,
+        budget: u64,
+    ) -> Result<Self, SizeError> {
+        let (width, height) = area(min, max, std::mem::size_of::<Cell>() as u64, budget)?;
+
+        Ok(DenseMap {
+            width: width as usize,
+            origin: min,
+            sources,
+            bounds,
+            floor: None,
+            cells: vec![Cell::Air; (width * height) as usize],
+        })
+    }
+
+    /// Line and column of `coord` in `cells`, if it is inside the map.
+    fn position(&self, coord: Coord) -> Option<(usize, usize)> {
+        let y = usize::try_from(coord.0.checked_sub(self.origin.0)?).ok()?;
+        let x = usize::try_from(coord.1.checked_sub(self.origin.1)?).ok()?;
+
+        (y < self.height() && x < self.width()).then_some((y, x))
+    }
+
+    fn cell_mut(&mut self, coord: Coord) -> &mut Cell {
+        let (y, x) = self
+            .position(coord)
+            .unwrap_or_else(|| panic!("{coord:?} is outside of the map"));
+        &mut self.line_mut(y)[x]
+    }
+
+    fn height(&self) -> usize {
+        self.cells.len() / self.width()
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn line(&self, y: usize) -> &[Cell] {
+        &self.cells[self.line_range(y)]
+    }
+
+    fn line_mut(&mut self, y: usize) -> &mut [Cell] {
+        let r = self.line_range(y);
+        &mut self.cells[r]
+    }
+
+    fn line_range(&self, y: usize) -> Range<usize> {
+        let start = y * self.width();
+        let end = start + self.width();
+
+        start..end
+    }
+
+    fn lines(&self) -> impl Iterator<Item = &[Cell]> + '_ {
+        (0..self.height()).map(|y| self.line(y))
+    }
+}
+impl Map for DenseMap {
+    fn sources(&self) -> &[Coord] {
+        &self.sources
+    }
+
+    fn cell(&self, coord: Coord) -> Option<Cell> {
+        let (y, x) = self.position(coord)?;
+        Some(self.line(y)[x])
+    }
+
+    fn set(&mut self, coord: Coord) {
+        *self.cell_mut(coord) = Cell::Sand;
+    }
+
+    fn bounds(&self) -> (Coord, Coord) {
+        self.bounds
+    }
+
+    fn occupied(&self) -> BTreeMap<Coord, Cell> {
+        self.lines()
+            .enumerate()
+            .flat_map(|(y, line)| {
+                let y = self.origin.0 + y as i64;
+                line.iter()
+                    .enumerate()
+                    .filter(|&(_, &cell)| cell != Cell::Air)
+                    .map(move |(x, &cell)| (Coord(y, self.origin.1 + x as i64), cell))
+            })
+            .filter(|(coord, _)| Some(coord.0) != self.floor)
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Coord(i64, i64);
+impl FromStr for Coord {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (from, to) = s
+            .split_once(',')
+            .ok_or_else(|| ParseError::BadCoord(s.to_string()))?;
+
+        let x = from
+            .parse()
+            .map_err(|e| ParseError::BadCoordNumber(e, s.to_string()))?;
+        let y = to
+            .parse()
+            .map_err(|e| ParseError::BadCoordNumber(e, s.to_string()))?;
+
+        Ok(Coord(y, x))
+    }
+}
+impl Coord {
+    /// Smallest and largest line and column of `coords`.
+    fn bounds<I: IntoIterator<Item = Coord>>(coords: I) -> Option<(Coord, Coord)> {
+        let mut coords = coords.into_iter();
+        let first = coords.next()?;
+
+        Some(coords.fold((first, first), |(min, max), coord| {
+            (
+                Coord(min.0.min(coord.0), min.1.min(coord.1)),
+                Coord(max.0.max(coord.0), max.1.max(coord.1)),
+            )
+        }))
+    }
+
+    /// Moves one cell towards `torwards` along both axes, which follows
+    /// straight and 45° segments. Compares rather than subtracts, so it
+    /// can't overflow even between the ends of the `i64` range.
+    fn step(&mut self, torwards: Coord) {
+        self.0 += torwards.0.cmp(&self.0) as i64;
+        self.1 += torwards.1.cmp(&self.1) as i64;
+    }
+}
+
+/// Whether rock paths may have 45° segments.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Diagonals {
+    #[default]
+    Reject,
+    Allow,
+}
+impl FromStr for Diagonals {
+    type Err = UnknownDiagonals;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "reject" => Ok(Diagonals::Reject),
+            "allow" => Ok(Diagonals::Allow),
+            _ => Err(UnknownDiagonals(s.to_string())),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Unknown diagonals {0:?}, expected reject or allow")]
+struct UnknownDiagonals(String);
+
+#[derive(Debug, Clone)]
+struct Movement(Vec<Coord>);
+impl FromStr for Movement {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Movement::parse(s, Diagonals::Reject)
+    }
+}
+impl Movement {
+    fn parse(s: &str, diagonals: Diagonals) -> Result<Self, ParseError> {
+        let movement = s
+            .split(" -> ")
+            .map(Coord::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (index, segment) in movement.windows(2).enumerate() {
+            let dy = segment[1].0.abs_diff(segment[0].0);
+            let dx = segment[1].1.abs_diff(segment[0].1);
+            let straight = dy == 0 || dx == 0;
+            let diagonal = dy == dx;
+
+            let allowed = straight || (diagonals == Diagonals::Allow && diagonal);
+            if !allowed {
+                let text = s.split(" -> ").skip(index).take(2).join(" -> ");
+                return Err(ParseError::Skewed(index + 1, text, diagonals));
+            }
+        }
+
+        Ok(Movement(movement))
+    }
+}
+impl IntoIterator for Movement {
+    type Item = Coord;
+    type IntoIter = MovementIterator;
+
+    fn into_iter(self) -> Self::IntoIter {
+        MovementIterator(self.0, 1)
+    }
+}
+impl IntoIterator for &Movement {
+    type Item = Coord;
+    type IntoIter = MovementIterator;
+
+    fn into_iter(self) -> Self::IntoIter {
+        MovementIterator(self.0.clone(), 1)
+    }
+}
+
+struct MovementIterator(Vec<Coord>, usize);
+impl Iterator for MovementIterator {
+    type Item = Coord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.len() <= self.1 {
+            return None;
+        }
+
+        let (first, second) = self.0.split_at_mut(self.1);
+        let first = &mut first[first.len() - 1];
+        let second = &second[0];
+
+        let r = *first;
+        if Coord::eq(first, second) {
+            self.1 += 1;
+        } else {
+            first.step(*second);
+        }
+        Some(r)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+enum ParseError {
+    #[error("{0}")]
+    Input(#[from] InputError),
+    #[error("Bad coordinate {0:?}")]
+    BadCoord(String),
+    #[error("{0}, bad coordinate {0:?}")]
+    BadCoordNumber(ParseIntError, String),
+    #[error("Segment {0} {1:?} is {}", match .2 {
+        Diagonals::Reject => "neither horizontal nor vertical",
+        Diagonals::Allow => "neither horizontal, vertical nor at 45°",
+    })]
+    Skewed(usize, String, Diagonals),
+}
+impl From<ParseError> for crate::Error {
+    fn from(value: ParseError) -> Self {
+        crate::Error::Parsing(value.into())
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+enum SizeError {
+    #[error("Cave coordinates overflow")]
+    Overflow,
+    #[error("A cave of {width}x{height} cells takes more than {budget} bytes")]
+    OverBudget {
+        width: u64,
+        height: u64,
+        budget: u64,
+    },
+}
+impl From<SizeError> for crate::Error {
+    fn from(value: SizeError) -> Self {
+        crate::Error::Semantic(value.into())
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Exceeded {0} grains without termination")]
+struct TooManyGrains(u32);
+impl From<TooManyGrains> for crate::Error {
+    fn from(value: TooManyGrains) -> Self {
+        crate::Error::Semantic(value.into())
+    }
+}
+
+/// Where sand comes from, as `x,y` coordinates separated by `;`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Sources(Vec<Coord>);
+impl Default for Sources {
+    fn default() -> Self {
+        Sources(vec![START])
+    }
+}
+impl FromStr for Sources {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Sources(
+            s.split(';')
+                .map(|x| x.trim().parse())
+                .collect::<Result<_, _>>()?,
+        ))
+    }
+}
+
+#[derive(Debug)]
+struct Config {
+    sources: Sources,
+    diagonals: Diagonals,
+    /// Print the cave on stderr every this many grains, and once full. With
+    /// 0, only once full.
+    animate: Option<u32>,
+    /// Cells shown around the rocks when printing the cave.
+    margin: i64,
+    /// Most bytes the map of the cave may take.
+    budget: u64,
+    /// Print where the grains falling into the abyss leave the cave.
+    verbose: bool,
+    /// Print how many grains settled and how fast every half second.
+    progress: bool,
+    /// Fail after this many grains settled.
+    max_grains: Option<u32>,
+    /// Where to write the cave filled up to the sources, as a list of cells
+    /// for a `.txt` path, otherwise as an image.
+    dump_cave: Option<PathBuf>,
+}
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            sources: Default::default(),
+            diagonals: Default::default(),
+            animate: None,
+            margin: 1,
+            budget: MapConfig::BUDGET,
+            verbose: false,
+            progress: false,
+            max_grains: None,
+            dump_cave: None,
+        }
+    }
+}
+impl Config {
+    fn from_params(params: &crate::Params) -> crate::Result<Self> {
+        Ok(Config {
+            sources: params.parse("source")?.unwrap_or_default(),
+            diagonals: params.parse("diagonals")?.unwrap_or_default(),
+            animate: params
+                .parse("animate")?
+                .or_else(|| params.flag("animate").then_some(1)),
+            margin: params.parse("margin")?.unwrap_or(1),
+            budget: params.parse("memory-budget")?.unwrap_or(MapConfig::BUDGET),
+            verbose: params.verbose(),
+            progress: params.flag("progress"),
+            max_grains: params.parse("max-grains")?,
+            dump_cave: params.parse("dump-cave")?,
+        })
+    }
+
+    /// Fills the cave of `rocks` under `part`, on a [`DenseMap`] unless it
+    /// takes more than the memory budget.
+    fn simulate(&self, rocks: &[Movement], part: MapConfig) -> crate::Result<u32> {
+        let part = MapConfig {
+            budget: self.budget,
+            ..part
+        };
+
+        match DenseMap::from_rocks(rocks, self.sources.0.clone(), part) {
+            Ok(mut dense) => self.run(&mut dense, part.boundary),
+            Err(SizeError::OverBudget { .. }) => {
+                let sparse = rocks.iter().cloned().collect::<SparseMap>();
+                let sparse = sparse.with_sources(self.sources.0.clone());
+                self.run(&mut sparse.with_config(part), part.boundary)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn run<M: Map>(&self, map: &mut M, boundary: Boundary) -> crate::Result<u32> {
+        let mut simulation = Simulation::new(map, self);
+        let grains = simulation.run_to_completion()?;
+
+        match boundary {
+            Boundary::Floor(_) => {
+                if let Some(path) = &self.dump_cave {
+                    simulation.dump(path)?;
+                }
+            }
+            Boundary::Abyss => {
+                if self.verbose {
+                    simulation.report_escapes(100);
+                }
+            }
+        }
+
+        Ok(grains)
+    }
+}
+
+/// Sand dropped on a map a grain at a time, from each source in turn until
+/// a grain from it falls into the abyss or it is covered.
+struct Simulation<'a, M> {
+    map: &'a mut M,
+    config: &'a Config,
+    /// Way down from each source still dropping sand: right after a grain
+    /// settles, the next one only goes back up to where it came to rest.
+    paths: Vec<Vec<Coord>>,
+    /// Index in `paths` of the next source to drop from.
+    next: usize,
+    grains: u32,
+    /// What became of the grain that stopped the last source.
+    last: Outcome,
+}
+impl<'a, M: Map> Simulation<'a, M> {
+    fn new(map: &'a mut M, config: &'a Config) -> Self {
+        let paths = map.sources().iter().map(|&source| vec![source]).collect();
+        Simulation {
+            map,
+            config,
+            paths,
+            next: 0,
+            grains: 0,
+            last: Outcome::SourceBlocked,
+        }
+    }
+
+    /// Drops a grain from the next source. Once finished, grains go the way
+    /// of the last one, without changing the map.
+    fn drop_grain(&mut self) -> Outcome {
+        let several = self.paths.len() > 1;
+        let Some(path) = self.paths.get_mut(self.next) else {
+            return self.last;
+        };
+
+        // Sand from the other sources may have settled on the path.
+        if several {
+            if let Some(blocked) = path.iter().position(|&c| self.map.empty(c) != Some(true)) {
+                path.truncate(blocked);
+            }
+        }
+
+        let outcome = self.map.sand_along(path);
+        match outcome {
+            Outcome::Settled(_) => {
+                self.grains += 1;
+                self.next += 1;
+            }
+            Outcome::FellIntoAbyss(_) | Outcome::SourceBlocked => {
+                self.paths.remove(self.next);
+                self.last = outcome;
+            }
+        }
+        if self.next >= self.paths.len() {
+            self.next = 0;
+        }
+
+        outcome
+    }
+
+    fn grains_settled(&self) -> u32 {
+        self.grains
+    }
+
+    /// Whether no more sand can settle.
+    fn is_finished(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Drops grains until finished, returning how many settled in total.
+    /// Shows the animation and the progress of the config, and fails after
+    /// its maximum of grains.
+    fn run_to_completion(&mut self) -> crate::Result<u32> {
+        let mut progress = self.config.progress.then(Progress::new);
+
+        while !self.is_finished() {
+            if !matches!(self.drop_grain(), Outcome::Settled(_)) {
+                continue;
+            }
+
+            let grains = self.grains_settled();
+            if self
+                .config
+                .animate
+                .is_some_and(|every| grains.is_multiple_of(every))
+            {
+                self.frame();
+            }
+            if let Some(progress) = &mut progress {
+                progress.update(grains);
+            }
+            if let Some(max) = self.config.max_grains.filter(|&max| grains > max) {
+                return Err(TooManyGrains(max).into());
+            }
+        }
+
+        if self.config.animate.is_some() {
+            self.frame();
+        }
+
+        Ok(self.grains_settled())
+    }
+
+    fn frame(&self) {
+        eprintln!(
+            "\x1b[2J\x1b[H{}\n{} grains",
+            self.map.render(self.config.margin),
+            self.grains
+        );
+    }
+
+    /// Columns from which up to `count` more grains, dropped in turn from
+    /// the sources that are not covered, leave the map. Nothing can settle
+    /// once finished, so this only makes sense afterwards.
+    fn escapes(&mut self, count: usize) -> BTreeMap<i64, usize> {
+        let mut sources = self.map.sources().to_vec();
+        let mut columns = BTreeMap::new();
+        let mut dropped = 0;
+
+        while dropped < count && !sources.is_empty() {
+            let source = sources[dropped % sources.len()];
+            match self.map.sand_along(&mut vec![source]) {
+                Outcome::FellIntoAbyss(exit) => *columns.entry(exit.1).or_default() += 1,
+                Outcome::Settled(_) => (),
+                Outcome::SourceBlocked => {
+                    sources.retain(|&x| x != source);
+                    continue;
+                }
+            }
+            dropped += 1;
+        }
+
+        columns
+    }
+
+    /// Prints the [`Simulation::escapes`] of `count` grains on stderr.
+    fn report_escapes(&mut self, count: usize) {
+        let escapes = self.escapes(count);
+        if !escapes.is_empty() {
+            eprintln!(
+                "Exit columns of {} grains:",
+                escapes.values().sum::<usize>()
+            );
+        }
+        for (x, count) in escapes {
+            eprintln!("{x:>6} {count}");
+        }
+    }
+
+    /// Writes the cave to `path`, see [`Config::dump_cave`].
+    fn dump(&self, path: &Path) -> crate::Result<()> {
+        match CaveFormat::of(path) {
+            CaveFormat::Pgm => pgm::save(path, &cave_image(&*self.map, self.config.budget)?)?,
+            CaveFormat::Text => pgm::save_with(path, |file| write_cells(file, &*self.map))?,
+        }
+        Ok(())
+    }
+}
+
+/// How `--dump-cave` writes the cave.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CaveFormat {
+    /// Grayscale image.
+    Pgm,
+    /// `x,y,kind` lines.
+    Text,
+}
+impl CaveFormat {
+    fn of(path: &Path) -> Self {
+        match path.extension() {
+            Some(extension) if extension == "txt" => CaveFormat::Text,
+            _ => CaveFormat::Pgm,
+        }
+    }
+}
+
+/// The rocks and sand of `map` in an image just large enough for them and
+/// the sources, unless it would take more than `budget` bytes.
+fn cave_image(map: &impl Map, budget: u64) -> Result<Grid<u8>, SizeError> {
+    let occupied = map.occupied();
+    let coords = occupied.keys().chain(map.sources()).copied();
+    let Some((min, max)) = Coord::bounds(coords) else {
+        return Ok(Grid::default());
+    };
+
+    let (width, height) = area(min, max, 1, budget)?;
+    let mut image = Grid::filled(width as usize, height as usize, Cell::Air.shade());
+    for (coord, cell) in occupied {
+        image[((coord.1 - min.1) as usize, (coord.0 - min.0) as usize)] = cell.shade();
+    }
+
+    Ok(image)
+}
+
+/// The rocks and sand of `map` as `x,y,kind` lines.
+fn write_cells<W: Write>(mut w: W, map: &impl Map) -> io::Result<()> {
+    for (coord, cell) in map.occupied() {
+        writeln!(w, "{},{},{}", coord.1, coord.0, cell.name())?;
+    }
+    Ok(())
+}
+
+/// Prints how many grains settled and how fast, every half second.
+struct Progress {
+    last: Instant,
+    last_grains: u32,
+}
+impl Progress {
+    const PERIOD: Duration = Duration::from_millis(500);
+
+    fn new() -> Self {
+        Progress {
+            last: Instant::now(),
+            last_grains: 0,
+        }
+    }
+
+    fn update(&mut self, grains: u32) {
+        // Keeps the clock out of most grains.
+        if !grains.is_multiple_of(1024) {
+            return;
+        }
+
+        let elapsed = self.last.elapsed();
+        if elapsed >= Progress::PERIOD {
+            let rate = f64::from(grains - self.last_grains) / elapsed.as_secs_f64();
+            eprintln!("{grains} grains settled, {rate:.0} grains/s");
+            self.last = Instant::now();
+            self.last_grains = grains;
+        }
+    }
+}
+
+fn answer<I: Input>(input: I, config: &Config) -> crate::Result<Answer> {
+    let movement = input.map(|line| Movement::parse(&line?, config.diagonals));
+    let rocks = movement.collect::<Result<Vec<_>, _>>()?;
+
+    let turns = config.simulate(&rocks, MapConfig::PART1)?;
+    let turns_to_fill = config.simulate(&rocks, MapConfig::PART2)?;
+
+    Ok(Answer {
+        part1: turns,
+        part2: turns_to_fill,
+    })
+}
+
+pub fn main() -> crate::Exit {
+    run().into()
+}
+
+pub fn solve(input: crate::input::InputImpl, params: &crate::Params) -> crate::Result<String> {
+    let config = Config::from_params(params)?;
+    Ok(answer(input, &config)?.to_string())
+}
+
+fn run() -> crate::Result<()> {
+    let config = Config::from_params(&crate::params()?)?;
+    crate::main_impl(DAY, |input| answer(input, &config))
+}
+
+#[test]
+fn d14_example() {
+    assert_eq!(
+        answer(crate::input(DAY, true), &Config::default()).unwrap(),
+        Answer {
+            part1: 24,
+            part2: 93,
+        }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use crate::input::input_from_str;
+
+    use super::*;
+
+    impl DenseMap {
+        /// The rocks of `map` under `config`.
+        fn new(map: &SparseMap, config: MapConfig) -> Result<Self, SizeError> {
+            let bounds = (map.min, map.max);
+            let mut dense = DenseMap::empty(bounds, map.sources.clone(), config)?;
+            for (&coord, &cell) in &map.cells {
+                *dense.cell_mut(coord) = cell;
+            }
+
+            Ok(dense)
+        }
+    }
+
+    /// Drops a grain all the way from the first source.
+    fn sand(map: &mut impl Map) -> Outcome {
+        sand_from(map, map.sources()[0])
+    }
+
+    fn sand_from(map: &mut impl Map, source: Coord) -> Outcome {
+        map.sand_along(&mut vec![source])
+    }
+
+    /// Runs a whole [`Simulation`] on `map`.
+    fn fill(map: &mut impl Map) -> u32 {
+        let config = Config::default();
+        Simulation::new(map, &config).run_to_completion().unwrap()
+    }
+
+    /// [`fill`] dropping every grain all the way from its source.
+    fn fill_slowly(map: &mut impl Map) -> u32 {
+        let mut sources = map.sources().to_vec();
+        let mut grains = 0;
+        while !sources.is_empty() {
+            sources.retain(|&source| match sand_from(map, source) {
+                Outcome::Settled(_) => {
+                    grains += 1;
+                    true
+                }
+                Outcome::FellIntoAbyss(_) | Outcome::SourceBlocked => false,
+            });
+        }
+
+        grains
+    }
+
+    fn example_rocks() -> impl Iterator<Item = Movement> {
+        crate::input(DAY, true).map(|line| Movement::from_str(&line.unwrap()).unwrap())
+    }
+
+    fn example() -> SparseMap {
+        example_rocks().collect()
+    }
+
+    /// Deterministic pseudo random rock lines around the start.
+    fn random_rocks<T>(seed: &mut u64, count: usize, spread: i64, rows: Range<i64>) -> T
+    where
+        T: FromIterator<Movement>,
+    {
+        let mut next = |bound: i64| {
+            *seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((*seed >> 33) % bound as u64) as i64
+        };
+
+        (0..count)
+            .map(|_| {
+                let from = Coord(
+                    rows.start + next(rows.end - rows.start),
+                    START.1 - spread + next(2 * spread),
+                );
+                let length = next(spread / 2 + 1);
+                let to = match next(2) {
+                    0 => Coord(from.0, from.1 + length),
+                    _ => Coord(from.0 + length, from.1),
+                };
+                Movement(vec![from, to])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn backends() {
+        let rocks = example();
+        for (config, expected) in [(MapConfig::PART1, 24), (MapConfig::PART2, 93)] {
+            assert_eq!(fill_slowly(&mut rocks.with_config(config)), expected);
+            assert_eq!(fill(&mut rocks.with_config(config)), expected);
+            assert_eq!(
+                fill_slowly(&mut DenseMap::new(&rocks, config).unwrap()),
+                expected
+            );
+            assert_eq!(fill(&mut DenseMap::new(&rocks, config).unwrap()), expected);
+        }
+    }
+
+    #[test]
+    fn sand_outcomes() {
+        let rocks = example();
+        let mut sparse = rocks.with_config(MapConfig::PART1);
+        let mut dense = DenseMap::new(&rocks, MapConfig::PART1).unwrap();
+        assert_eq!(sand(&mut sparse), Outcome::Settled(Coord(8, 500)));
+        assert_eq!(sand(&mut dense), Outcome::Settled(Coord(8, 500)));
+        assert_eq!(fill_slowly(&mut sparse), 23);
+        assert_eq!(fill_slowly(&mut dense), 23);
+        assert_eq!(sand(&mut sparse), Outcome::FellIntoAbyss(Coord(8, 494)));
+        assert_eq!(sand(&mut dense), Outcome::FellIntoAbyss(Coord(8, 494)));
+
+        let mut sparse = rocks.with_config(MapConfig::PART2);
+        let mut dense = DenseMap::new(&rocks, MapConfig::PART2).unwrap();
+        assert_eq!(fill_slowly(&mut sparse), 93);
+        assert_eq!(fill_slowly(&mut dense), 93);
+        assert_eq!(sparse.empty(START), Some(false));
+        assert_eq!(sand(&mut sparse), Outcome::SourceBlocked);
+        assert_eq!(sand(&mut dense), Outcome::SourceBlocked);
+    }
+
+    #[test]
+    fn simulation() {
+        let config = Config::default();
+        let mut dense = DenseMap::new(&example(), MapConfig::PART1).unwrap();
+        let mut simulation = Simulation::new(&mut dense, &config);
+        let mut outcomes = vec![];
+        while !simulation.is_finished() {
+            outcomes.push(simulation.drop_grain());
+            assert_eq!(simulation.grains_settled() as usize, outcomes.len().min(24));
+        }
+        assert_eq!(outcomes.len(), 25);
+        assert_eq!(outcomes[0], Outcome::Settled(Coord(8, 500)));
+        assert!(outcomes[..24]
+            .iter()
+            .all(|x| matches!(x, Outcome::Settled(_))));
+        assert_eq!(outcomes[24], Outcome::FellIntoAbyss(Coord(8, 494)));
+        assert_eq!(simulation.drop_grain(), outcomes[24]);
+        assert_eq!(simulation.grains_settled(), 24);
+
+        let mut sparse = example().with_config(MapConfig::PART2);
+        let mut simulation = Simulation::new(&mut sparse, &config);
+        assert_eq!(simulation.run_to_completion().unwrap(), 93);
+        assert!(simulation.is_finished());
+        assert_eq!(simulation.drop_grain(), Outcome::SourceBlocked);
+        assert_eq!(simulation.grains_settled(), 93);
+    }
+
+    #[test]
+    fn render() {
+        let after = |grains| {
+            let mut dense = DenseMap::new(&example(), MapConfig::PART1).unwrap();
+            let mut sparse = example().with_config(MapConfig::PART1);
+            for _ in 0..grains {
+                sand(&mut dense);
+                sand(&mut sparse);
+            }
+
+            assert_eq!(dense.render(0), sparse.render(0));
+            dense.render(0)
+        };
+
+        assert_eq!(
+            after(5),
+            [
+                "......+...",
+                "..........",
+                "..........",
+                "..........",
+                "....#...##",
+                "....#...#.",
+                "..###...#.",
+                "......o.#.",
+                "....oooo#.",
+                "#########.",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            after(22),
+            [
+                "......+...",
+                "..........",
+                "......o...",
+                ".....ooo..",
+                "....#ooo##",
+                "....#ooo#.",
+                "..###ooo#.",
+                "....oooo#.",
+                "...ooooo#.",
+                "#########.",
+            ]
+            .join("\n")
+        );
+        assert_eq!(after(0).lines().next(), Some("......+..."));
+        let margin = example().render(1);
+        assert_eq!(margin.lines().next(), Some("............"));
+        assert_eq!(margin.lines().nth(1), Some(".......+...."));
+    }
+
+    #[test]
+    fn animate() {
+        let config = Config {
+            animate: Some(10),
+            margin: 2,
+            ..Config::default()
+        };
+
+        assert_eq!(
+            answer(crate::input(DAY, true), &config).unwrap(),
+            Answer {
+                part1: 24,
+                part2: 93,
+            }
+        );
+
+        // Only the final frame.
+        let config = Config {
+            animate: Some(0),
+            ..Config::default()
+        };
+        assert_eq!(answer(crate::input(DAY, true), &config).unwrap().part1, 24);
+    }
+
+    #[test]
+    fn moved_source() {
+        let config = Config {
+            sources: "499,0".parse().unwrap(),
+            ..Config::default()
+        };
+
+        assert_eq!(
+            answer(crate::input(DAY, true), &config).unwrap(),
+            Answer {
+                part1: 20,
+                part2: 65,
+            }
+        );
+
+        let rocks = example().with_sources(config.sources.0);
+        assert_eq!(fill_slowly(&mut rocks.with_config(MapConfig::PART1)), 20);
+        assert_eq!(fill_slowly(&mut rocks.with_config(MapConfig::PART2)), 65);
+    }
+
+    #[test]
+    fn two_sources() {
+        // A cup right under the second source takes two grains, the second
+        // one covering the source.
+        let rocks = example_rocks()
+            .chain([Movement(vec![
+                Coord(2, 519),
+                Coord(3, 519),
+                Coord(3, 521),
+                Coord(2, 521),
+            ])])
+            .collect::<SparseMap>()
+            .with_sources(vec![START, Coord(1, 520)]);
+
+        let mut dense = DenseMap::new(&rocks, MapConfig::PART1).unwrap();
+        assert_eq!(fill(&mut dense), 24 + 2);
+        assert_eq!(sand_from(&mut dense, Coord(1, 520)), Outcome::SourceBlocked);
+        assert!(matches!(
+            sand_from(&mut dense, START),
+            Outcome::FellIntoAbyss(_)
+        ));
+        assert_eq!(fill_slowly(&mut rocks.with_config(MapConfig::PART1)), 26);
+
+        let expected = fill_slowly(&mut rocks.with_config(MapConfig::PART2));
+        let mut dense = DenseMap::new(&rocks, MapConfig::PART2).unwrap();
+        assert_eq!(fill(&mut dense), expected);
+        assert_eq!(sand_from(&mut dense, Coord(1, 520)), Outcome::SourceBlocked);
+        assert_eq!(sand_from(&mut dense, START), Outcome::SourceBlocked);
+    }
+
+    #[test]
+    fn segments() {
+        let error = Movement::from_str("498,4 -> 498,6 -> 500,8").unwrap_err();
+        assert!(
+            matches!(&error, ParseError::Skewed(2, segment, _) if segment == "498,6 -> 500,8"),
+            "{error}"
+        );
+        assert!(Movement::parse("0,0 -> 3,2", Diagonals::Allow).is_err());
+
+        let diagonal = Movement::parse("0,0 -> 3,3", Diagonals::Allow).unwrap();
+        assert_eq!(
+            diagonal.into_iter().collect::<Vec<_>>(),
+            [Coord(0, 0), Coord(1, 1), Coord(2, 2), Coord(3, 3)]
+        );
+
+        let allowed = crate::input(DAY, true)
+            .map(|line| Movement::parse(&line.unwrap(), Diagonals::Allow).unwrap())
+            .collect::<SparseMap>();
+        assert_eq!(allowed.cells, example().cells);
+        assert_eq!(example().cells.len(), 20);
+
+        let input = input_from_str("498,4 -> 498,6 -> 500,8");
+        assert!(answer(input.into_iter(), &Config::default()).is_err());
+
+        // Segments spanning the whole i64 range.
+        let (min, max) = (i64::MIN, i64::MAX);
+        let across = Movement::from_str(&format!("{min},0 -> {max},0")).unwrap();
+        assert_eq!(across.into_iter().nth(1), Some(Coord(0, min + 1)));
+        let diagonal = format!("{min},{min} -> {max},{max}");
+        assert!(Movement::parse(&diagonal, Diagonals::Allow).is_ok());
+        let skewed = format!("{min},{min} -> {max},{}", max - 1);
+        assert!(matches!(
+            Movement::parse(&skewed, Diagonals::Allow),
+            Err(ParseError::Skewed(1, ..))
+        ));
+
+        let mut corner = Coord(min, max);
+        corner.step(Coord(max, min));
+        assert_eq!(corner, Coord(min + 1, max - 1));
+    }
+
+    #[test]
+    fn budget() {
+        // Too wide for a dense map, simulated on the sparse one.
+        let far = [Movement::from_str("2000000000,3 -> 2000000000,5").unwrap()];
+        assert!(matches!(
+            DenseMap::from_rocks(&far, vec![START], MapConfig::PART1),
+            Err(SizeError::OverBudget { .. })
+        ));
+        let input = far.map(|_| Ok("2000000000,3 -> 2000000000,5".to_string()));
+        assert_eq!(
+            answer(input.into_iter(), &Config::default()).unwrap(),
+            Answer {
+                part1: 0,
+                part2: 49,
+            }
+        );
+
+        let rocks = Movement::from_str("-9223372036854775800,3 -> -9223372036854775800,5")
+            .into_iter()
+            .collect::<SparseMap>();
+        assert!(matches!(
+            DenseMap::new(&rocks, MapConfig::PART2),
+            Err(SizeError::Overflow)
+        ));
+        assert!(matches!(
+            Coord::from_str("99999999999999999999,0"),
+            Err(ParseError::BadCoordNumber(..))
+        ));
+
+        // The example without a floor takes 10x10 cells, with the floor
+        // 25x12 cells.
+        let rocks = example_rocks().collect::<Vec<_>>();
+        let dense = |boundary, budget| {
+            let config = MapConfig { boundary, budget };
+            DenseMap::from_rocks(&rocks, vec![START], config).map(|dense| dense.cells.len())
+        };
+        assert!(dense(Boundary::Abyss, 99).is_err());
+        assert_eq!(dense(Boundary::Abyss, 100).unwrap(), 100);
+        assert!(dense(Boundary::Floor(2), 25 * 12 - 1).is_err());
+        assert_eq!(dense(Boundary::Floor(2), 25 * 12).unwrap(), 25 * 12);
+
+        for budget in [0, 99, 100, 25 * 12] {
+            let config = Config {
+                budget,
+                ..Config::default()
+            };
+            assert_eq!(
+                answer(crate::input(DAY, true), &config).unwrap(),
+                Answer {
+                    part1: 24,
+                    part2: 93,
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn max_grains() {
+        let config = Config {
+            max_grains: Some(24 + 93),
+            progress: true,
+            ..Config::default()
+        };
+        assert_eq!(
+            answer(crate::input(DAY, true), &config).unwrap(),
+            Answer {
+                part1: 24,
+                part2: 93,
+            }
+        );
+
+        // A closed box around the source, big enough to never fill up.
+        let input = ["400,-1 -> 600,-1 -> 600,1000 -> 400,1000 -> 400,-1".to_string()];
+        let config = Config {
+            max_grains: Some(10_000),
+            ..Config::default()
+        };
+        let error = answer(input.map(Ok).into_iter(), &config).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Exceeded 10000 grains without termination"
+        );
+    }
+
+    #[test]
+    fn dump() {
+        let mut dense = DenseMap::new(&example(), MapConfig::PART2).unwrap();
+        fill(&mut dense);
+
+        let mut image = vec![];
+        pgm::write(&mut image, &cave_image(&dense, MapConfig::BUDGET).unwrap()).unwrap();
+        let header = b"P5\n21 11\n255\n";
+        assert_eq!(&image[..header.len()], header);
+        let pixels = &image[header.len()..];
+        assert_eq!(pixels.len(), 21 * 11);
+        let count = |cell: Cell| pixels.iter().filter(|&&x| x == cell.shade()).count();
+        assert_eq!(count(Cell::Sand), 93);
+        assert_eq!(count(Cell::Rock), example().cells.len());
+
+        assert!(matches!(
+            cave_image(&dense, 21 * 11 - 1),
+            Err(SizeError::OverBudget {
+                width: 21,
+                height: 11,
+                ..
+            })
+        ));
+
+        let mut text = vec![];
+        write_cells(&mut text, &dense).unwrap();
+        let text = String::from_utf8(text).unwrap();
+        assert_eq!(text.lines().count(), 93 + 20);
+        assert_eq!(text.lines().filter(|x| x.ends_with(",sand")).count(), 93);
+        assert!(text.contains("\n498,4,rock\n"));
+        assert!(text.starts_with("500,0,sand\n"));
+
+        assert_eq!(CaveFormat::of(Path::new("cave.txt")), CaveFormat::Text);
+        assert_eq!(CaveFormat::of(Path::new("cave.pgm")), CaveFormat::Pgm);
+    }
+
+    #[test]
+    fn escapes() {
+        let config = Config::default();
+        let mut dense = DenseMap::new(&example(), MapConfig::PART1).unwrap();
+        let mut simulation = Simulation::new(&mut dense, &config);
+        assert_eq!(simulation.run_to_completion().unwrap(), 24);
+        assert_eq!(simulation.escapes(100), BTreeMap::from([(494, 100)]));
+        assert_eq!(simulation.run_to_completion().unwrap(), 24);
+        assert_eq!(fill(&mut dense), 0);
+
+        let rocks = example().with_sources(vec![START, Coord(0, 510)]);
+        let mut dense = DenseMap::new(&rocks, MapConfig::PART1).unwrap();
+        let mut simulation = Simulation::new(&mut dense, &config);
+        simulation.run_to_completion().unwrap();
+        assert_eq!(simulation.escapes(5), BTreeMap::from([(494, 3), (510, 2)]));
+
+        let config = Config {
+            verbose: true,
+            ..Config::default()
+        };
+        assert_eq!(answer(crate::input(DAY, true), &config).unwrap().part1, 24);
+    }
+
+    #[test]
+    fn backends_agree() {
+        let mut seed = 1984;
+        for case in 0..100 {
+            let rocks: SparseMap = random_rocks(&mut seed, 8, 12, 1..16);
+            for config in [MapConfig::PART1, MapConfig::PART2] {
+                let mut sparse = rocks.with_config(config);
+                let mut dense = DenseMap::new(&rocks, config).unwrap();
+                let grains = (fill_slowly(&mut sparse), fill(&mut dense));
+                // Where the next grain falls, or that it is blocked.
+                let outcomes = (sand(&mut sparse), sand(&mut dense));
+
+                let mismatch = || {
+                    format!(
+                        "case {case} with {:?}:\n{}\n\n{}",
+                        config.boundary,
+                        sparse.render(3),
+                        dense.render(3)
+                    )
+                };
+                assert_eq!(grains.0, grains.1, "{}", mismatch());
+                assert_eq!(sparse.occupied(), dense.occupied(), "{}", mismatch());
+                assert_eq!(outcomes.0, outcomes.1, "{}", mismatch());
+            }
+        }
+    }
+
+    #[test]
+    fn from_rocks() {
+        let check = |rocks: Vec<Movement>| {
+            let sparse = rocks.iter().cloned().collect::<SparseMap>();
+            for config in [MapConfig::PART1, MapConfig::PART2] {
+                let mut direct = DenseMap::from_rocks(&rocks, vec![START], config).unwrap();
+                let mut copied = DenseMap::new(&sparse, config).unwrap();
+                assert_eq!(direct.occupied(), copied.occupied());
+                assert_eq!(direct.origin, copied.origin);
+                assert_eq!(direct.cells, copied.cells);
+                assert_eq!(direct.bounds, copied.bounds);
+
+                assert_eq!(fill(&mut direct), fill(&mut copied));
+                assert_eq!(direct.occupied(), copied.occupied());
+            }
+        };
+
+        check(example_rocks().collect());
+        let mut seed = 1985;
+        for _ in 0..50 {
+            check(random_rocks(&mut seed, 8, 12, 1..16));
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn from_rocks_timing() {
+        // A million rock cells, in a thousand lines of a thousand.
+        let rocks = (0..1000)
+            .map(|y| Movement(vec![Coord(2 * y + 1, 0), Coord(2 * y + 1, 999)]))
+            .collect::<Vec<_>>();
+
+        let begin = Instant::now();
+        let sparse = rocks.iter().cloned().collect::<SparseMap>();
+        let copied = DenseMap::new(&sparse, MapConfig::PART2).unwrap();
+        let copied_time = begin.elapsed();
+
+        let begin = Instant::now();
+        let direct = DenseMap::from_rocks(&rocks, vec![START], MapConfig::PART2).unwrap();
+        let direct_time = begin.elapsed();
+
+        eprintln!("{copied_time:?} through the sparse map, {direct_time:?} directly");
+        assert_eq!(copied.cells, direct.cells);
+        assert!(direct_time < copied_time);
+    }
+
+    #[test]
+    fn offset_rocks() {
+        let rocks = [
+            Movement(vec![Coord(7, 480), Coord(7, 490)]),
+            Movement(vec![Coord(5, 485), Coord(9, 485)]),
+        ]
+        .into_iter()
+        .collect::<SparseMap>();
+        let dense = DenseMap::new(&rocks, MapConfig::PART1).unwrap();
+
+        assert_eq!((dense.height(), dense.width()), (10, 21));
+        assert_eq!(dense.origin, Coord(0, 480));
+        assert_eq!(dense.sources(), [START]);
+        assert_eq!(dense.empty(START), Some(true));
+        assert_eq!(dense.empty(Coord(7, 480)), Some(false));
+        assert_eq!(dense.empty(Coord(7, 479)), None);
+
+        for config in [MapConfig::PART1, MapConfig::PART2] {
+            assert_eq!(
+                fill(&mut DenseMap::new(&rocks, config).unwrap()),
+                fill_slowly(&mut rocks.with_config(config))
+            );
+        }
+    }
+
+    #[test]
+    fn rocks_above_start() {
+        let rocks = [
+            Movement(vec![Coord(-3, 495), Coord(-3, 505)]),
+            Movement(vec![Coord(4, 498), Coord(4, 502)]),
+        ]
+        .into_iter()
+        .collect::<SparseMap>();
+        let dense = DenseMap::new(&rocks, MapConfig::PART1).unwrap();
+
+        assert_eq!(dense.origin, Coord(-3, 495));
+        assert_eq!(dense.empty(Coord(-3, 500)), Some(false));
+        assert_eq!(
+            fill(&mut DenseMap::new(&rocks, MapConfig::PART2).unwrap()),
+            fill_slowly(&mut rocks.with_config(MapConfig::PART2))
+        );
+    }
+
+    #[test]
+    #[ignore]
+    fn fill_timing() {
+        // About the size of the full inputs: rocks from line 15 to 170, 50
+        // columns around the start.
+        let rocks: SparseMap = random_rocks(&mut 1976, 150, 50, 15..170);
+
+        let begin = Instant::now();
+        let slow = fill_slowly(&mut rocks.with_config(MapConfig::PART2));
+        let slow_time = begin.elapsed();
+
+        let begin = Instant::now();
+        let fast = fill(&mut DenseMap::new(&rocks, MapConfig::PART2).unwrap());
+        let fast_time = begin.elapsed();
+
+        eprintln!("{slow} grains in {slow_time:?} from the start, {fast_time:?} with the path");
+        assert_eq!(slow, fast);
+        assert!(fast_time < slow_time);
+    }
+}
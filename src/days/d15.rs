@@ -0,0 +1,1424 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeSet, HashSet},
+    num::ParseIntError,
+    ops::RangeInclusive,
+    str::FromStr,
+};
+
+use crate::{
+    grid::Grid,
+    input::{Input, InputError},
+    Answer, Part, Parts,
+};
+
+pub const DAY: u32 = 15;
+
+/// A sensor whose diamond, one position beyond its closest beacon, fits in
+/// `i64` along both diagonals and so along both axes.
+#[derive(Debug)]
+struct Sensor {
+    sensor: Coord,
+    closest_beacon: Coord,
+    distance: i64,
+}
+impl Sensor {
+    fn new(sensor: Coord, closest_beacon: Coord) -> Result<Sensor, ParseError> {
+        let overflow = || ParseError::ReachOverflow(sensor, closest_beacon);
+        let distance = sensor
+            .distance_to(closest_beacon)
+            .and_then(|distance| i64::try_from(distance).ok())
+            .ok_or_else(overflow)?;
+
+        let Coord(x, y) = sensor;
+        let reach = distance.checked_add(1).ok_or_else(overflow)?;
+        let fits = [x.checked_add(y), x.checked_sub(y)]
+            .into_iter()
+            .all(|diagonal| {
+                diagonal.is_some_and(|diagonal| {
+                    diagonal.checked_sub(reach).is_some() && diagonal.checked_add(reach).is_some()
+                })
+            });
+        if !fits {
+            return Err(overflow());
+        }
+
+        Ok(Sensor {
+            sensor,
+            closest_beacon,
+            distance,
+        })
+    }
+
+    fn distance(&self) -> i64 {
+        self.distance
+    }
+
+    /// Columns of `row` at most as far as the closest beacon.
+    fn coverage(&self, row: i64) -> Option<SensorRange> {
+        let offset = self
+            .distance
+            .unsigned_abs()
+            .checked_sub(row.abs_diff(self.sensor.1))?;
+        let offset = i64::try_from(offset).ok()?;
+
+        Some(SensorRange::new(
+            self.sensor.0.checked_sub(offset)?,
+            self.sensor.0.checked_add(offset)?,
+        ))
+    }
+
+    fn covers(&self, coord: Coord) -> bool {
+        self.sensor
+            .distance_to(coord)
+            .is_some_and(|distance| distance <= self.distance.unsigned_abs())
+    }
+}
+impl FromStr for Sensor {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad_input_line = || ParseError::BadInputLine(s.to_string());
+
+        let (sensor, closest_beacon) = s.split_once(": ").ok_or_else(bad_input_line)?;
+
+        let sensor = sensor
+            .strip_prefix("Sensor at ")
+            .ok_or_else(bad_input_line)?;
+        let sensor = sensor.parse()?;
+
+        let closest_beacon = closest_beacon
+            .strip_prefix("closest beacon is at ")
+            .ok_or_else(bad_input_line)?;
+        let closest_beacon = closest_beacon.parse()?;
+
+        Sensor::new(sensor, closest_beacon)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Coord(i64, i64);
+impl FromStr for Coord {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad_coord = || ParseError::BadCoord(s.to_string());
+        let bad_number = |e| ParseError::BadNumber(s.to_string(), e);
+
+        let (x, y) = s.split_once(", ").ok_or_else(bad_coord)?;
+
+        let x = x.strip_prefix("x=").ok_or_else(bad_coord)?;
+        let y = y.strip_prefix("y=").ok_or_else(bad_coord)?;
+
+        let x = x.parse().map_err(bad_number)?;
+        let y = y.parse().map_err(bad_number)?;
+
+        Ok(Coord(x, y))
+    }
+}
+impl Coord {
+    /// Manhattan distance, `None` if it doesn't fit in `u64`.
+    fn distance_to(self, to: Coord) -> Option<u64> {
+        self.0.abs_diff(to.0).checked_add(self.1.abs_diff(to.1))
+    }
+
+    /// The tuning frequency of a distress beacon here, with `multiplier`
+    /// times its x plus its y.
+    fn tuning_frequency(self, multiplier: u64) -> Result<u64, FrequencyOverflow> {
+        let overflow = || FrequencyOverflow(self, multiplier);
+        let x = u64::try_from(self.0).map_err(|_| overflow())?;
+        let y = u64::try_from(self.1).map_err(|_| overflow())?;
+
+        x.checked_mul(multiplier)
+            .and_then(|x| x.checked_add(y))
+            .ok_or_else(overflow)
+    }
+}
+
+#[repr(transparent)]
+#[derive(PartialEq, Eq, Debug, Clone)]
+struct SensorRange(pub RangeInclusive<i64>);
+impl From<RangeInclusive<i64>> for SensorRange {
+    fn from(value: RangeInclusive<i64>) -> Self {
+        Self(value)
+    }
+}
+impl PartialOrd for SensorRange {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(Self::cmp(self, other))
+    }
+}
+impl Ord for SensorRange {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.0.start().cmp(other.0.start()) {
+            Ordering::Equal => self.0.end().cmp(other.0.end()).reverse(),
+            definitive => definitive,
+        }
+    }
+}
+impl SensorRange {
+    fn new(first: i64, last: i64) -> Self {
+        SensorRange(first..=last)
+    }
+
+    fn first(&self) -> i64 {
+        *self.0.start()
+    }
+
+    fn last(&self) -> i64 {
+        *self.0.end()
+    }
+
+    fn join(self, rhs: SensorRange) -> (SensorRange, Option<SensorRange>) {
+        if self.last() + 1 < rhs.first() {
+            return (self, Some(rhs));
+        }
+
+        (
+            SensorRange::new(self.first(), self.last().max(rhs.last())),
+            None,
+        )
+    }
+
+    fn join_all<'a, I: IntoIterator<Item = SensorRange> + 'a>(
+        range: I,
+    ) -> impl Iterator<Item = SensorRange> + 'a {
+        let mut range = range.into_iter();
+        let mut prev = range.next();
+
+        std::iter::from_fn(move || {
+            let prev2 = prev.take()?;
+
+            let Some(current) = range.next() else {
+                return Some(Some(prev2));
+            };
+
+            let (first, second) = prev2.join(current);
+
+            match second {
+                Some(second) => {
+                    prev = Some(second);
+                    Some(Some(first))
+                }
+                None => {
+                    prev = Some(first);
+                    Some(None)
+                }
+            }
+        })
+        .flatten()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn len(&self) -> u64 {
+        match self.is_empty() {
+            true => 0,
+            false => (self.last() + 1 - self.first()) as u64,
+        }
+    }
+
+    /// The part of the range from `a` to `b`, `None` if they do not meet.
+    fn clamp(self, a: i64, b: i64) -> Option<SensorRange> {
+        let clamped = SensorRange::new(self.first().max(a), self.last().min(b));
+        (!clamped.is_empty()).then_some(clamped)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+enum ParseError {
+    #[error("{0}")]
+    Input(#[from] InputError),
+    #[error("Bad input line {0:?}")]
+    BadInputLine(String),
+    #[error("Bad coordinate format {0:?}")]
+    BadCoord(String),
+    #[error("{1} bad number on coordinate {0:?}")]
+    BadNumber(String, ParseIntError),
+    #[error("Reach of sensor at {0:?} with beacon at {1:?} out of range")]
+    ReachOverflow(Coord, Coord),
+}
+impl From<ParseError> for crate::Error {
+    fn from(value: ParseError) -> Self {
+        crate::Error::Parsing(value.into())
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Tuning frequency of a distress beacon at {0:?} with multiplier {1} out of range")]
+struct FrequencyOverflow(Coord, u64);
+impl From<FrequencyOverflow> for crate::Error {
+    fn from(value: FrequencyOverflow) -> Self {
+        crate::Error::Semantic(value.into())
+    }
+}
+
+/// Counts and extent of `sensors`, to check the search bounds against.
+fn summary(sensors: &[Sensor]) -> String {
+    let beacons = sensors
+        .iter()
+        .map(|sensor| sensor.closest_beacon)
+        .collect::<HashSet<_>>();
+    let mut summary = format!("{} sensors, {} beacons", sensors.len(), beacons.len());
+
+    let radius = Sensor::distance;
+    let extent = (
+        sensors.iter().map(|s| s.sensor.0 - radius(s)).min(),
+        sensors.iter().map(|s| s.sensor.1 - radius(s)).min(),
+        sensors.iter().map(|s| s.sensor.0 + radius(s)).max(),
+        sensors.iter().map(|s| s.sensor.1 + radius(s)).max(),
+        sensors.iter().map(Sensor::distance).max(),
+    );
+    if let (Some(x0), Some(y0), Some(x1), Some(y1), Some(radius)) = extent {
+        summary +=
+            &format!(", in reach from x={x0}, y={y0} to x={x1}, y={y1}, radius up to {radius}");
+    }
+
+    summary
+}
+
+/// Positions of `row` in reach of a sensor, which cannot hold a beacon other
+/// than the known `beacons`.
+fn excluded_positions(sensors: &[Sensor], beacons: &HashSet<Coord>, row: i64) -> u64 {
+    let ranges = sensors
+        .iter()
+        .filter_map(|sensor| sensor.coverage(row))
+        .collect::<BTreeSet<_>>();
+
+    let mut on_row = beacons
+        .iter()
+        .filter(|beacon| beacon.1 == row)
+        .map(|beacon| beacon.0)
+        .collect::<Vec<_>>();
+    on_row.sort_unstable();
+    let mut on_row = on_row.into_iter().peekable();
+
+    SensorRange::join_all(ranges)
+        .map(|range| {
+            while on_row.next_if(|&x| x < range.first()).is_some() {}
+            let mut known = 0;
+            while on_row.next_if(|&x| x <= range.last()).is_some() {
+                known += 1;
+            }
+            range.len() - known
+        })
+        .sum()
+}
+
+/// How part 2 looks for the distress beacon.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Algorithm {
+    /// Every row in turn for a column out of reach of all sensors.
+    Rows,
+    /// Along the lines just out of reach of the sensors.
+    #[default]
+    Diamonds,
+}
+impl FromStr for Algorithm {
+    type Err = UnknownAlgorithm;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rows" => Ok(Algorithm::Rows),
+            "diamonds" => Ok(Algorithm::Diamonds),
+            other => Err(UnknownAlgorithm(other.to_string())),
+        }
+    }
+}
+impl Algorithm {
+    /// Places counted before giving up on an ambiguous search.
+    const COUNTED: usize = 1000;
+
+    /// The only position from `0` to `limit` on both axes out of reach of
+    /// all `sensors`.
+    fn search(self, sensors: &[Sensor], limit: i64) -> Result<Coord, SearchError> {
+        match self {
+            Algorithm::Rows => Algorithm::single(scan_rows(sensors, limit), limit),
+            Algorithm::Diamonds => match Algorithm::single(diamond_edges(sensors, limit), limit) {
+                // Nothing on the edges, so either the whole area is in reach
+                // or none of it is.
+                Err(SearchError::NoDistressBeacon { .. })
+                    if !sensors.iter().any(|sensor| sensor.covers(Coord(0, 0))) =>
+                {
+                    Algorithm::Rows.search(sensors, limit)
+                }
+                found => found,
+            },
+        }
+    }
+
+    fn single<I>(mut found: I, limit: i64) -> Result<Coord, SearchError>
+    where
+        I: Iterator<Item = Coord>,
+    {
+        let first = found
+            .next()
+            .ok_or(SearchError::NoDistressBeacon { limit })?;
+        let Some(second) = found.next() else {
+            return Ok(first);
+        };
+
+        Err(SearchError::AmbiguousDistressBeacon {
+            count: 2 + found.take(Algorithm::COUNTED - 2).count(),
+            first_two: [first, second],
+        })
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Unknown search algorithm {0:?}, expected rows or diamonds")]
+struct UnknownAlgorithm(String);
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+enum SearchError {
+    #[error("No place for the distress beacon up to {limit}")]
+    NoDistressBeacon { limit: i64 },
+    #[error(
+        "At least {count} places for the distress beacon, first {:?} and {:?}",
+        first_two[0],
+        first_two[1]
+    )]
+    AmbiguousDistressBeacon { count: usize, first_two: [Coord; 2] },
+}
+impl From<SearchError> for crate::Error {
+    fn from(value: SearchError) -> Self {
+        crate::Error::Semantic(value.into())
+    }
+}
+
+/// Every position out of reach, row by row.
+fn scan_rows(sensors: &[Sensor], limit: i64) -> impl Iterator<Item = Coord> + '_ {
+    (0..=limit).flat_map(move |y| {
+        let ranges = sensors
+            .iter()
+            .filter_map(|sensor| sensor.coverage(y))
+            .collect::<BTreeSet<_>>();
+
+        gaps(ranges, 0, limit)
+            .into_iter()
+            .flatten()
+            .map(move |x| Coord(x, y))
+    })
+}
+
+/// Columns from `first` to `last` between the `ranges`, and before and
+/// after them.
+fn gaps(ranges: BTreeSet<SensorRange>, first: i64, last: i64) -> Vec<RangeInclusive<i64>> {
+    let mut gaps = vec![];
+    // None once a range reaches the end of i64.
+    let mut next = Some(first);
+    for range in SensorRange::join_all(ranges).filter_map(|range| range.clamp(first, last)) {
+        let Some(from) = next else { break };
+        if range.first() > from {
+            gaps.push(from..=range.first() - 1);
+        }
+        next = range.last().checked_add(1);
+    }
+    gaps.extend(next.map(|from| from..=last));
+
+    gaps
+}
+
+/// With positions both in and out of reach, those out of reach next to
+/// one in reach are just out of reach of a sensor, on the edge of its
+/// diamond. So these are at least two positions out of reach if there are
+/// several, and the only one if there is one.
+fn diamond_edges(sensors: &[Sensor], limit: i64) -> impl Iterator<Item = Coord> {
+    let mut edges = BTreeSet::new();
+    for sensor in sensors {
+        let Coord(x, y) = sensor.sensor;
+        let reach = sensor.distance() + 1;
+        edges.extend([Diagonal::Sum(x + y - reach), Diagonal::Sum(x + y + reach)]);
+        edges.extend([
+            Diagonal::Difference(x - y - reach),
+            Diagonal::Difference(x - y + reach),
+        ]);
+    }
+
+    let found = edges.into_iter().flat_map(|edge| {
+        let columns = edge.columns(limit);
+        let ranges = sensors
+            .iter()
+            .filter_map(|sensor| edge.coverage(sensor))
+            .collect::<BTreeSet<_>>();
+
+        gaps(ranges, columns.first(), columns.last())
+            .into_iter()
+            .flatten()
+            .map(move |x| edge.at(x))
+    });
+
+    found.collect::<BTreeSet<_>>().into_iter()
+}
+
+/// A line at 45°, as the x + y or the x - y of its positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Diagonal {
+    Sum(i64),
+    Difference(i64),
+}
+impl Diagonal {
+    fn at(self, x: i64) -> Coord {
+        match self {
+            Diagonal::Sum(sum) => Coord(x, sum - x),
+            Diagonal::Difference(difference) => Coord(x, x - difference),
+        }
+    }
+
+    /// Columns of the line from `0` to `limit` on both axes, empty if it
+    /// misses that area.
+    fn columns(self, limit: i64) -> SensorRange {
+        match self {
+            Diagonal::Sum(sum) => {
+                SensorRange::new(sum.saturating_sub(limit).max(0), sum.min(limit))
+            }
+            Diagonal::Difference(difference) => SensorRange::new(
+                difference.max(0),
+                limit.saturating_add(difference).min(limit),
+            ),
+        }
+    }
+
+    /// Columns of the line at most as far from `sensor` as its closest
+    /// beacon.
+    fn coverage(self, sensor: &Sensor) -> Option<SensorRange> {
+        let Coord(x, y) = sensor.sensor;
+        // Along the line, the distance to the sensor is |dx| + |offset - dx|
+        // for dx columns away from it.
+        // Too far to reach if the offset doesn't even fit.
+        let offset = match self {
+            Diagonal::Sum(sum) => sum.checked_sub(x + y)?,
+            Diagonal::Difference(difference) => difference.checked_sub(x - y)?,
+        };
+        let distance = sensor.distance();
+        if offset.unsigned_abs() > distance.unsigned_abs() {
+            return None;
+        }
+
+        // Both ends are at most `distance` from the sensor, but twice that may
+        // not fit.
+        let half = |n: i128| i64::try_from(n.div_euclid(2)).ok();
+        let (offset, distance) = (i128::from(offset), i128::from(distance));
+        Some(SensorRange::new(
+            x.checked_add(half(offset - distance + 1)?)?,
+            x.checked_add(half(offset + distance)?)?,
+        ))
+    }
+}
+
+/// What part 2 gives of the distress beacon.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Output {
+    #[default]
+    Frequency,
+    Coords,
+}
+impl FromStr for Output {
+    type Err = UnknownOutput;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "freq" => Ok(Output::Frequency),
+            "coords" => Ok(Output::Coords),
+            other => Err(UnknownOutput(other.to_string())),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Unknown output {0:?}, expected freq or coords")]
+struct UnknownOutput(String);
+
+/// The distress beacon as given by [`Output`].
+#[derive(Clone, PartialEq, Eq)]
+enum Part2 {
+    Frequency(u64),
+    Coords(String),
+}
+impl std::fmt::Debug for Part2 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part2::Frequency(frequency) => frequency.fmt(f),
+            Part2::Coords(coords) => coords.fmt(f),
+        }
+    }
+}
+impl std::fmt::Display for Part2 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part2::Frequency(frequency) => frequency.fmt(f),
+            Part2::Coords(coords) => coords.fmt(f),
+        }
+    }
+}
+
+/// Where to look, which differs between the example and the full input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Config {
+    /// Row on which to count the positions that cannot hold a beacon.
+    row: i64,
+    /// Largest coordinate of the distress beacon, on both axes.
+    limit: i64,
+    algorithm: Algorithm,
+    /// Times the x of the distress beacon in its tuning frequency.
+    multiplier: u64,
+    output: Output,
+    /// Log a summary of the sensors, where the distress beacon was found
+    /// and the sensor ranges on its row.
+    verbose: bool,
+    /// Log the sensors, beacons and positions in reach in `window`.
+    visualize: bool,
+    window: Window,
+}
+impl Config {
+    const EXAMPLE: Config = Config {
+        row: 10,
+        limit: 20,
+        algorithm: Algorithm::Diamonds,
+        multiplier: 4000000,
+        output: Output::Frequency,
+        verbose: false,
+        visualize: false,
+        window: Window::EXAMPLE,
+    };
+    const FULL: Config = Config {
+        row: 2000000,
+        limit: 4000000,
+        algorithm: Algorithm::Diamonds,
+        multiplier: 4000000,
+        output: Output::Frequency,
+        verbose: false,
+        visualize: false,
+        window: Window::EXAMPLE,
+    };
+
+    fn from_params(params: &crate::Params) -> crate::Result<Self> {
+        let default = match params.example() {
+            true => Config::EXAMPLE,
+            false => Config::FULL,
+        };
+
+        Ok(Config {
+            row: params.parse("row")?.unwrap_or(default.row),
+            limit: params.parse("limit")?.unwrap_or(default.limit),
+            algorithm: params.parse("algo")?.unwrap_or_default(),
+            multiplier: params.parse("multiplier")?.unwrap_or(default.multiplier),
+            output: params.parse("output")?.unwrap_or_default(),
+            verbose: params.verbose(),
+            visualize: params.flag("visualize"),
+            window: Window::from_params(params)?,
+        })
+    }
+}
+
+/// Area drawn by `--visualize`, bounds included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Window {
+    x0: i64,
+    x1: i64,
+    y0: i64,
+    y1: i64,
+}
+impl Window {
+    /// The area of the diagrams of the puzzle statement.
+    const EXAMPLE: Window = Window {
+        x0: -4,
+        x1: 26,
+        y0: 0,
+        y1: 22,
+    };
+    /// Most cells drawn, each one checked against every sensor.
+    const MAX_CELLS: u64 = 1 << 20;
+
+    fn from_params(params: &crate::Params) -> crate::Result<Self> {
+        let default = Window::EXAMPLE;
+
+        Ok(Window {
+            x0: params.parse("x0")?.unwrap_or(default.x0),
+            x1: params.parse("x1")?.unwrap_or(default.x1),
+            y0: params.parse("y0")?.unwrap_or(default.y0),
+            y1: params.parse("y1")?.unwrap_or(default.y1),
+        })
+    }
+
+    fn width(self) -> u64 {
+        self.x1.saturating_sub(self.x0).saturating_add(1).max(0) as u64
+    }
+
+    fn height(self) -> u64 {
+        self.y1.saturating_sub(self.y0).saturating_add(1).max(0) as u64
+    }
+}
+
+/// `window` as in the puzzle statement: sensors as `S`, beacons as `B`, the
+/// other positions in reach of a sensor as `#`.
+fn render(sensors: &[Sensor], window: Window) -> Result<String, WindowTooLarge> {
+    let cells = window.width().saturating_mul(window.height());
+    if cells > Window::MAX_CELLS {
+        return Err(WindowTooLarge(window, cells));
+    }
+
+    let mut grid = Grid::filled(window.width() as usize, window.height() as usize, '.');
+    let position = |Coord(x, y): Coord| ((x - window.x0) as usize, (y - window.y0) as usize);
+    for (x, y) in grid.coords().collect::<Vec<_>>() {
+        let coord = Coord(window.x0 + x as i64, window.y0 + y as i64);
+        if sensors.iter().any(|sensor| sensor.covers(coord)) {
+            grid[(x, y)] = '#';
+        }
+    }
+    for sensor in sensors {
+        for (coord, symbol) in [(sensor.closest_beacon, 'B'), (sensor.sensor, 'S')] {
+            if (window.x0..=window.x1).contains(&coord.0)
+                && (window.y0..=window.y1).contains(&coord.1)
+            {
+                grid[position(coord)] = symbol;
+            }
+        }
+    }
+
+    let lines = (0..grid.height()).map(|y| grid.line(y).iter().collect::<String>());
+    Ok(lines.collect::<Vec<_>>().join("\n"))
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Window {0:?} of {1} cells too large to draw, at most {max}", max = Window::MAX_CELLS)]
+struct WindowTooLarge(Window, u64);
+impl From<WindowTooLarge> for crate::Error {
+    fn from(value: WindowTooLarge) -> Self {
+        crate::Error::Semantic(value.into())
+    }
+}
+
+/// Solves the selected `parts`, giving the verbose diagnostics to `log`, a
+/// line at a time.
+fn answer<I, L>(
+    input: I,
+    config: &Config,
+    parts: Parts,
+    mut log: L,
+) -> crate::Result<Answer<Part<u64>, Part<Part2>>>
+where
+    I: Input,
+    L: FnMut(String),
+{
+    let sensors = input.parsed::<Sensor>().collect::<Result<Vec<_>, _>>()?;
+    if config.verbose {
+        log(summary(&sensors));
+    }
+    if config.visualize {
+        log(render(&sensors, config.window)?);
+    }
+
+    let part1 = parts.part1(|| {
+        let beacons = sensors
+            .iter()
+            .map(|sensor| sensor.closest_beacon)
+            .collect::<HashSet<_>>();
+        Ok::<_, crate::Error>(excluded_positions(&sensors, &beacons, config.row))
+    })?;
+
+    let part2 = parts.part2(|| {
+        let beacon = config.algorithm.search(&sensors, config.limit)?;
+        let Coord(x, y) = beacon;
+        let part2 = match config.output {
+            Output::Frequency => Part2::Frequency(beacon.tuning_frequency(config.multiplier)?),
+            Output::Coords => Part2::Coords(format!("{x},{y}")),
+        };
+
+        if config.verbose {
+            let ranges = sensors
+                .iter()
+                .filter_map(|sensor| sensor.coverage(y))
+                .collect::<BTreeSet<_>>();
+            let ranges = SensorRange::join_all(ranges)
+                .map(|range| format!("{:?}", range.0))
+                .collect::<Vec<_>>();
+            log(format!("Row {y} in reach of {}", ranges.join(", ")));
+            match &part2 {
+                Part2::Frequency(frequency) => log(format!(
+                    "Distress beacon at x={x}, y={y}, tuning frequency {frequency}"
+                )),
+                Part2::Coords(_) => log(format!("Distress beacon at x={x}, y={y}")),
+            }
+        }
+
+        Ok::<_, crate::Error>(part2)
+    })?;
+
+    Ok(Answer { part1, part2 })
+}
+
+pub fn main() -> crate::Exit {
+    run().into()
+}
+
+pub fn solve(input: crate::input::InputImpl, params: &crate::Params) -> crate::Result<String> {
+    let config = Config::from_params(params)?;
+    let answer = answer(input, &config, params.parts(), |line| eprintln!("{line}"))?;
+    Ok(answer
+        .with_extra("Row", config.row)
+        .with_extra("Limit", config.limit)
+        .to_string())
+}
+
+fn run() -> crate::Result<()> {
+    let config = Config::from_params(&crate::params()?)?;
+    crate::main_impl_parts(DAY, |input, parts| {
+        // Labelled with the row and limit, which differ between the example
+        // and the full input.
+        let answer = answer(input, &config, parts, |line| eprintln!("{line}"))?;
+        Ok(answer
+            .with_extra("Row", config.row)
+            .with_extra("Limit", config.limit))
+    })
+}
+
+#[test]
+fn d15_example() {
+    assert_eq!(
+        tests::answer(crate::input(DAY, true), &Config::EXAMPLE).unwrap(),
+        Answer {
+            part1: 26,
+            part2: Part2::Frequency(56000011),
+        }
+    )
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::time::Instant;
+
+    use crate::input::{input_from_lines, input_from_path};
+
+    use super::*;
+
+    /// Both parts of the answer, with the diagnostics on stderr.
+    pub(super) fn answer<I: Input>(input: I, config: &Config) -> crate::Result<Answer<u64, Part2>> {
+        answer_logging(input, config, |line| eprintln!("{line}"))
+    }
+
+    /// Both parts of the answer, giving the diagnostics to `log`.
+    fn answer_logging<I, L>(input: I, config: &Config, log: L) -> crate::Result<Answer<u64, Part2>>
+    where
+        I: Input,
+        L: FnMut(String),
+    {
+        let answer = super::answer(input, config, Parts::default(), log)?;
+        Ok(Answer {
+            part1: answer.part1.unwrap(),
+            part2: answer.part2.unwrap(),
+        })
+    }
+
+    #[test]
+    fn parts() {
+        let answer = |parts| super::answer(crate::input(DAY, true), &Config::EXAMPLE, parts, drop);
+        let part1 = Parts {
+            part2: false,
+            ..Parts::default()
+        };
+        let part2 = Parts {
+            part1: false,
+            ..Parts::default()
+        };
+
+        let only1 = answer(part1).unwrap();
+        assert_eq!(only1.part1, Part::Solved(26));
+        assert_eq!(only1.part2, Part::Skipped);
+        let only2 = answer(part2).unwrap();
+        assert_eq!(only2.part1, Part::Skipped);
+        assert_eq!(only2.part2, Part::Solved(Part2::Frequency(56000011)));
+
+        // Part 1 still answers when there is no distress beacon.
+        let config = Config {
+            limit: 5,
+            ..Config::EXAMPLE
+        };
+        let no_beacon = super::answer(crate::input(DAY, true), &config, part1, drop);
+        assert_eq!(no_beacon.unwrap().part1, Part::Solved(26));
+        assert!(super::answer(crate::input(DAY, true), &config, part2, drop).is_err());
+    }
+
+    #[test]
+    fn ranges() {
+        let sensor = Sensor::new(Coord(8, 7), Coord(2, 10)).unwrap();
+        let beacons = HashSet::from([sensor.closest_beacon]);
+
+        assert_eq!(sensor.coverage(9), Some((1..=15).into()));
+        assert_eq!(sensor.coverage(10), Some((2..=14).into()));
+        assert_eq!(sensor.coverage(11), Some((3..=13).into()));
+        assert_eq!(sensor.coverage(17), None);
+        let sensors = [sensor];
+        assert_eq!(excluded_positions(&sensors, &beacons, 9), 15);
+        assert_eq!(excluded_positions(&sensors, &beacons, 10), 12);
+        assert_eq!(excluded_positions(&sensors, &beacons, 11), 11);
+
+        let sensor = Sensor::new(Coord(5, 5), Coord(5, 10)).unwrap();
+        let beacons = HashSet::from([sensor.closest_beacon]);
+
+        assert_eq!(sensor.coverage(10), Some((5..=5).into()));
+        assert_eq!(excluded_positions(&[sensor], &beacons, 10), 0);
+    }
+
+    #[test]
+    fn excluded() {
+        let sensors = crate::input(DAY, true)
+            .map(|line| line.unwrap().parse().unwrap())
+            .collect::<Vec<Sensor>>();
+        let beacons = sensors.iter().map(|sensor| sensor.closest_beacon).collect();
+        assert_eq!(excluded_positions(&sensors, &beacons, 10), 26);
+
+        // Ranges of -3..=3 and 8..=12 on row 2, with a beacon at the end of
+        // each.
+        let sensors = [
+            Sensor::new(Coord(0, 0), Coord(3, 2)).unwrap(),
+            Sensor::new(Coord(10, 0), Coord(8, 2)).unwrap(),
+        ];
+        let mut beacons = sensors.iter().map(|sensor| sensor.closest_beacon).collect();
+        assert_eq!(excluded_positions(&sensors, &beacons, 2), 7 + 5 - 2);
+        assert_eq!(excluded_positions(&sensors, &beacons, 0), 11 + 9);
+
+        // Out of reach of the sensors.
+        beacons.extend([Coord(5, 2), Coord(-4, 2), Coord(20, 2)]);
+        assert_eq!(excluded_positions(&sensors, &beacons, 2), 7 + 5 - 2);
+        beacons.insert(Coord(10, 2));
+        assert_eq!(excluded_positions(&sensors, &beacons, 2), 7 + 5 - 3);
+    }
+
+    #[test]
+    fn crlf_input() {
+        let example = std::fs::read_to_string("input/examples/d15").unwrap();
+        let path = std::env::temp_dir().join(format!("d15-crlf-{}", std::process::id()));
+        std::fs::write(&path, example.replace('\n', "\r\n")).unwrap();
+        let result = answer(input_from_path(&path), &Config::EXAMPLE);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(result.unwrap().part1, 26);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |lines: &[&str]| {
+            answer(input_from_lines(lines), &Config::EXAMPLE)
+                .unwrap_err()
+                .to_string()
+        };
+        let good = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15";
+
+        assert_eq!(
+            error(&[
+                good,
+                good,
+                "Sensor at x=2 y=18: closest beacon is at x=-2, y=15"
+            ]),
+            "line 3: Bad coordinate format \"x=2 y=18\""
+        );
+        assert_eq!(
+            error(&[good, "Sensor at x=2, y=18, closest beacon is at x=-2, y=15"]),
+            "line 2: Bad input line \"Sensor at x=2, y=18, closest beacon is at x=-2, y=15\""
+        );
+        assert_eq!(
+            error(&["Sensor at x=2, y=eighteen: closest beacon is at x=-2, y=15"]),
+            "line 1: invalid digit found in string bad number on coordinate \"x=2, y=eighteen\""
+        );
+    }
+
+    #[test]
+    fn visualize() {
+        let sensors = crate::input(DAY, true)
+            .map(|line| line.unwrap().parse().unwrap())
+            .collect::<Vec<Sensor>>();
+        let map = render(&sensors, Window::EXAMPLE).unwrap();
+        let rows = map.lines().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 23);
+        assert_eq!(rows[9], "...#########################...");
+        assert_eq!(rows[10], "..####B######################..");
+        assert_eq!(rows[11], ".###S#############.###########.");
+        assert_eq!(rows[10].matches('#').count(), 26);
+        // Sensor at 9,16 and its beacon right next to it.
+        assert_eq!(&rows[16][12..16], "#SB#");
+
+        let config = Config {
+            visualize: true,
+            ..Config::EXAMPLE
+        };
+        let mut lines = vec![];
+        answer_logging(crate::input(DAY, true), &config, |line| lines.push(line)).unwrap();
+        assert_eq!(lines, vec![map]);
+
+        let params = crate::Params::default()
+            .with("x0", Some("2"))
+            .with("x1", Some("4"))
+            .with("y1", Some("1"));
+        let window = Window::from_params(&params).unwrap();
+        assert_eq!(render(&sensors, window).unwrap(), "S##\n###");
+
+        let window = Window {
+            x1: 2000000,
+            ..Window::EXAMPLE
+        };
+        let error = render(&sensors, window).unwrap_err();
+        assert_eq!(error.1, 2000005 * 23);
+        assert!(error
+            .to_string()
+            .ends_with("too large to draw, at most 1048576"));
+        let empty = Window {
+            x1: -5,
+            ..Window::EXAMPLE
+        };
+        assert_eq!(render(&sensors, empty).unwrap(), "");
+    }
+
+    #[test]
+    fn input_summary() {
+        let sensors = crate::input(DAY, true)
+            .map(|line| line.unwrap().parse().unwrap())
+            .collect::<Vec<Sensor>>();
+        assert_eq!(
+            summary(&sensors),
+            "14 sensors, 6 beacons, in reach from x=-8, y=-10 to x=28, y=26, radius up to 10"
+        );
+        assert_eq!(
+            summary(&sensors[..1]),
+            "1 sensors, 1 beacons, in reach from x=-5, y=11 to x=9, y=25, radius up to 7"
+        );
+        assert_eq!(summary(&[]), "0 sensors, 0 beacons");
+    }
+
+    #[test]
+    fn large_coordinates() {
+        // Out of range of the former i32 coordinates and u32 distances.
+        assert!("3000000000".parse::<i32>().is_err());
+        let sensor = Sensor::from_str(
+            "Sensor at x=3000000000, y=-3000000000: closest beacon is at x=-1000000000, y=-2500000000",
+        )
+        .unwrap();
+        assert_eq!(sensor.distance(), 4500000000);
+        assert!(u32::try_from(sensor.distance()).is_err());
+
+        let coverage = sensor.coverage(-3000000000).unwrap();
+        assert_eq!(coverage, SensorRange::new(-1500000000, 7500000000));
+        assert_eq!(coverage.len(), 9000000001);
+        assert_eq!(
+            sensor.coverage(1000000000),
+            Some(SensorRange::new(2500000000, 3500000000))
+        );
+        assert_eq!(sensor.coverage(1500000001), None);
+
+        let target = Coord(3000000000, 2999999999);
+        assert_eq!(target.tuning_frequency(4000000).unwrap(), 12000002999999999);
+        let sensors = around(target, 4000000000);
+        assert_eq!(Algorithm::Diamonds.search(&sensors, 4000000000), Ok(target));
+
+        assert!(Coord(5000000000000, 0).tuning_frequency(4000000).is_err());
+        assert!(Coord(-1, 0).tuning_frequency(4000000).is_err());
+    }
+
+    #[test]
+    fn reach_overflow() {
+        let (min, max) = (i64::MIN, i64::MAX);
+        let overflows = |sensor, beacon| {
+            matches!(
+                Sensor::new(sensor, beacon),
+                Err(ParseError::ReachOverflow(..))
+            )
+        };
+        assert!(overflows(Coord(min, 0), Coord(max, 0)));
+        assert!(overflows(Coord(max, 0), Coord(max, 0)));
+        assert!(overflows(
+            Coord(max / 2 + 1, max / 2),
+            Coord(max / 2 + 1, max / 2)
+        ));
+        assert!(!overflows(Coord(max / 2, max / 2), Coord(max / 2, max / 2)));
+        let line = format!("Sensor at x={max}, y=0: closest beacon is at x={max}, y=0");
+        assert!(matches!(
+            Sensor::from_str(&line),
+            Err(ParseError::ReachOverflow(..))
+        ));
+
+        // As far as it gets, covering everything around the search area.
+        let sensor = Sensor::new(Coord(0, 0), Coord(max - 1, 0)).unwrap();
+        assert_eq!(sensor.coverage(0), Some(SensorRange::new(1 - max, max - 1)));
+        assert!(!sensor.covers(Coord(min, 0)));
+        let sensors = [sensor];
+        assert!(summary(&sensors).ends_with(&format!("radius up to {}", max - 1)));
+        for algorithm in [Algorithm::Rows, Algorithm::Diamonds] {
+            assert!(algorithm.search(&sensors, 10).is_err());
+        }
+        let far = Sensor::new(Coord(0, 1 - max / 2), Coord(0, 1 - max / 2)).unwrap();
+        assert_eq!(far.coverage(0), None);
+        let far = [far];
+        assert_eq!(
+            Algorithm::Diamonds.search(&far, 10),
+            Algorithm::Rows.search(&far, 10)
+        );
+    }
+
+    #[test]
+    fn order() {
+        assert!(SensorRange(1..=10) < SensorRange(2..=10));
+        assert!(SensorRange(1..=100) < SensorRange(1..=10));
+        assert!(SensorRange(1..=10) >= SensorRange(1..=100));
+    }
+
+    #[test]
+    fn join() {
+        assert_eq!(
+            SensorRange(1..=20).join(SensorRange(5..=10)),
+            (SensorRange(1..=20), None)
+        );
+
+        assert_eq!(
+            SensorRange(1..=20).join(SensorRange(5..=30)),
+            (SensorRange(1..=30), None)
+        );
+
+        assert_eq!(
+            SensorRange(1..=20).join(SensorRange(25..=30)),
+            (SensorRange(1..=20), Some(SensorRange(25..=30)))
+        );
+
+        assert_eq!(
+            SensorRange(1..=19).join(SensorRange(21..=30)),
+            (SensorRange(1..=19), Some(SensorRange(21..=30))),
+        );
+
+        assert_eq!(
+            SensorRange(1..=20).join(SensorRange(21..=30)),
+            (SensorRange(1..=30), None)
+        );
+    }
+
+    #[test]
+    fn params() {
+        let example = crate::Params::from_args(["-e".to_string()]);
+        assert_eq!(Config::from_params(&example).unwrap(), Config::EXAMPLE);
+        let full = crate::Params::default();
+        assert_eq!(Config::from_params(&full).unwrap(), Config::FULL);
+
+        let params = full.with("row", Some("11")).with("limit", Some("20"));
+        let config = Config::from_params(&params).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                row: 11,
+                limit: 20,
+                ..Config::FULL
+            }
+        );
+        assert_eq!(
+            answer(crate::input(DAY, true), &config).unwrap(),
+            Answer {
+                part1: 28,
+                part2: Part2::Frequency(56000011),
+            }
+        );
+
+        let params = crate::Params::default().with("row", Some("ten"));
+        assert!(Config::from_params(&params).is_err());
+        let params = crate::Params::default().with("algo", Some("rows"));
+        assert_eq!(
+            Config::from_params(&params).unwrap().algorithm,
+            Algorithm::Rows
+        );
+    }
+
+    #[test]
+    fn output() {
+        let part2 = |config: &Config| answer(crate::input(DAY, true), config).map(|x| x.part2);
+
+        let config = Config {
+            multiplier: 10,
+            ..Config::EXAMPLE
+        };
+        assert_eq!(part2(&config).unwrap(), Part2::Frequency(151));
+
+        let coords = crate::Params::from_args(["-e", "--output", "coords"].map(String::from));
+        let coords = Config::from_params(&coords).unwrap();
+        assert_eq!(part2(&coords).unwrap(), Part2::Coords("14,11".to_string()));
+        assert_eq!(
+            format!("{:?}", Part2::Coords("14,11".to_string())),
+            "\"14,11\""
+        );
+        assert_eq!(format!("{:?}", Part2::Frequency(151)), "151");
+
+        let overflow = crate::Params::from_args(
+            ["-e", "--multiplier", "18446744073709551615"].map(String::from),
+        );
+        let overflow = Config::from_params(&overflow).unwrap();
+        assert_eq!(overflow.multiplier, u64::MAX);
+        assert_eq!(
+            part2(&overflow).unwrap_err().to_string(),
+            "Tuning frequency of a distress beacon at Coord(14, 11) with multiplier 18446744073709551615 out of range"
+        );
+        let overflow = Config {
+            output: Output::Coords,
+            ..overflow
+        };
+        assert_eq!(
+            part2(&overflow).unwrap(),
+            Part2::Coords("14,11".to_string())
+        );
+    }
+
+    #[test]
+    fn diagnostics() {
+        let logged = |config: &Config| {
+            let mut lines = vec![];
+            let answer = answer_logging(crate::input(DAY, true), config, |line| lines.push(line));
+            assert_eq!(answer.unwrap().part2, Part2::Frequency(56000011));
+            lines
+        };
+
+        assert!(logged(&Config::EXAMPLE).is_empty());
+
+        let verbose = Config {
+            verbose: true,
+            ..Config::EXAMPLE
+        };
+        assert_eq!(
+            logged(&verbose),
+            vec![
+                "14 sensors, 6 beacons, in reach from x=-8, y=-10 to x=28, y=26, radius up to 10",
+                "Row 11 in reach of -3..=13, 15..=25",
+                "Distress beacon at x=14, y=11, tuning frequency 56000011",
+            ]
+        );
+
+        let rows = Config {
+            algorithm: Algorithm::Rows,
+            ..verbose
+        };
+        assert_eq!(logged(&rows), logged(&verbose));
+
+        let mut lines = vec![];
+        let config = Config {
+            limit: 10,
+            ..verbose
+        };
+        let error = answer_logging(crate::input(DAY, true), &config, |line| lines.push(line));
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "No place for the distress beacon up to 10"
+        );
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with("14 sensors"));
+    }
+
+    /// A sensor reaching `radius` cells away.
+    fn sensor(x: i64, y: i64, radius: i64) -> Sensor {
+        Sensor::new(Coord(x, y), Coord(x + radius, y)).unwrap()
+    }
+
+    /// Sensors reaching everything around `target` up to `limit` but it, each
+    /// `far` cells away from it on both axes.
+    fn around(target: Coord, far: i64) -> Vec<Sensor> {
+        let Coord(x, y) = target;
+        [(-1, -1), (1, -1), (-1, 1), (1, 1)]
+            .into_iter()
+            .map(|(dx, dy)| sensor(x + dx * far, y + dy * far, 2 * far - 1))
+            .collect()
+    }
+
+    fn uncovered(sensors: &[Sensor], limit: i64) -> Vec<Coord> {
+        (0..=limit)
+            .flat_map(|y| (0..=limit).map(move |x| Coord(x, y)))
+            .filter(|&coord| !sensors.iter().any(|sensor| sensor.covers(coord)))
+            .collect()
+    }
+
+    #[test]
+    fn algorithms() {
+        let sensors = crate::input(DAY, true)
+            .map(|line| line.unwrap().parse().unwrap())
+            .collect::<Vec<Sensor>>();
+        assert_eq!(uncovered(&sensors, 20), vec![Coord(14, 11)]);
+        for algorithm in [Algorithm::Rows, Algorithm::Diamonds] {
+            assert_eq!(algorithm.search(&sensors, 20), Ok(Coord(14, 11)));
+            assert_eq!(
+                algorithm.search(&sensors, 10),
+                Err(SearchError::NoDistressBeacon { limit: 10 })
+            );
+
+            let config = Config {
+                algorithm,
+                ..Config::EXAMPLE
+            };
+            let answer = answer(crate::input(DAY, true), &config).unwrap();
+            assert_eq!(answer.part2, Part2::Frequency(56000011));
+        }
+
+        for target in [Coord(7, 3), Coord(20, 7), Coord(0, 0), Coord(13, 20)] {
+            let sensors = around(target, 20);
+            assert_eq!(uncovered(&sensors, 20), vec![target]);
+            assert_eq!(Algorithm::Rows.search(&sensors, 20), Ok(target));
+            assert_eq!(Algorithm::Diamonds.search(&sensors, 20), Ok(target));
+        }
+    }
+
+    #[test]
+    fn search_errors() {
+        // All but the corners, then two of them.
+        let sensors = [sensor(2, 2, 3), sensor(4, 0, 0), sensor(0, 4, 0)];
+        let holes = vec![Coord(0, 0), Coord(4, 4)];
+        assert_eq!(uncovered(&sensors, 4), holes);
+        for algorithm in [Algorithm::Rows, Algorithm::Diamonds] {
+            let error = algorithm.search(&sensors, 4).unwrap_err();
+            assert_eq!(
+                error,
+                SearchError::AmbiguousDistressBeacon {
+                    count: 2,
+                    first_two: [Coord(0, 0), Coord(4, 4)],
+                }
+            );
+            assert_eq!(
+                error.to_string(),
+                "At least 2 places for the distress beacon, first Coord(0, 0) and Coord(4, 4)"
+            );
+
+            assert_eq!(
+                algorithm.search(&[sensor(2, 2, 4)], 4),
+                Err(SearchError::NoDistressBeacon { limit: 4 })
+            );
+
+            // Nothing in reach, with or without sensors.
+            let everywhere = || {
+                Err(SearchError::AmbiguousDistressBeacon {
+                    count: 25,
+                    first_two: [Coord(0, 0), Coord(1, 0)],
+                })
+            };
+            assert_eq!(algorithm.search(&[], 4), everywhere());
+            assert_eq!(algorithm.search(&[sensor(100, 0, 3)], 4), everywhere());
+
+            // 1,1 is between two diamonds touching along x + y = 2, on no
+            // crossing of their edges.
+            let sensors = [sensor(4, 4, 5), sensor(2, -2, 2), sensor(-2, -2, 5)];
+            assert_eq!(uncovered(&sensors, 4), vec![Coord(1, 1), Coord(0, 2)]);
+            assert!(matches!(
+                algorithm.search(&sensors, 4),
+                Err(SearchError::AmbiguousDistressBeacon { .. })
+            ));
+        }
+
+        let error = Algorithm::Rows.search(&[], 100).unwrap_err();
+        assert!(matches!(
+            error,
+            SearchError::AmbiguousDistressBeacon {
+                count: Algorithm::COUNTED,
+                first_two: [Coord(0, 0), Coord(1, 0)],
+            }
+        ));
+    }
+
+    #[test]
+    fn algorithms_agree() {
+        let mut seed = 1996u64;
+        let mut next = |bound: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((seed >> 33) % bound) as i64
+        };
+
+        for _ in 0..2000 {
+            let sensors = (0..1 + next(4))
+                .map(|_| sensor(next(12) - 3, next(12) - 3, 1 + next(6)))
+                .collect::<Vec<_>>();
+            let holes = uncovered(&sensors, 5);
+            let found = Algorithm::Diamonds.search(&sensors, 5);
+            match holes[..] {
+                [] => assert_eq!(found, Err(SearchError::NoDistressBeacon { limit: 5 })),
+                [beacon] => assert_eq!(found, Ok(beacon)),
+                _ => assert!(
+                    matches!(found, Err(SearchError::AmbiguousDistressBeacon { .. })),
+                    "{holes:?} but {found:?} for {sensors:?}"
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn beacon_on_bounds() {
+        // The beacon at 0,2 is only on edges of the first two sensors, which
+        // are parallel, so they never cross there.
+        let mut sensors = vec![sensor(2, 0, 3), sensor(-2, 4, 3), sensor(4, 4, 4)];
+
+        // Around the center of the search area.
+        let turn = |Coord(x, y): Coord| Coord(y, 4 - x);
+        for target in [Coord(0, 2), Coord(2, 4), Coord(4, 2), Coord(2, 0)] {
+            assert_eq!(uncovered(&sensors, 4), vec![target]);
+            assert_eq!(Algorithm::Rows.search(&sensors, 4), Ok(target));
+            assert_eq!(Algorithm::Diamonds.search(&sensors, 4), Ok(target));
+
+            for sensor in sensors.iter_mut() {
+                sensor.sensor = turn(sensor.sensor);
+                sensor.closest_beacon = turn(sensor.closest_beacon);
+            }
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn search_timing() {
+        let target = Coord(3123456, 2987654);
+        let sensors = around(target, 4000000);
+
+        let start = Instant::now();
+        assert_eq!(Algorithm::Rows.search(&sensors, 4000000), Ok(target));
+        let rows = start.elapsed();
+
+        let start = Instant::now();
+        assert_eq!(Algorithm::Diamonds.search(&sensors, 4000000), Ok(target));
+        let diamonds = start.elapsed();
+
+        println!("{rows:?} scanning the rows, {diamonds:?} along the diamonds");
+        assert!(diamonds < rows);
+    }
+
+    #[test]
+    fn clamp() {
+        let range = SensorRange::new(-5, 5);
+        assert_eq!(range.clone().clamp(-20, -6), None);
+        assert_eq!(range.clone().clamp(6, 20), None);
+        assert_eq!(range.clone().clamp(5, 20), Some(SensorRange::new(5, 5)));
+        assert_eq!(range.clone().clamp(-20, -5), Some(SensorRange::new(-5, -5)));
+        assert_eq!(range.clone().clamp(-20, 20), Some(range.clone()));
+        assert_eq!(range.clone().clamp(-2, 3), Some(SensorRange::new(-2, 3)));
+
+        assert!(SensorRange::new(1, 0).is_empty());
+        assert_eq!(SensorRange::new(1, 0).len(), 0);
+        assert_eq!(SensorRange::new(0, 0).len(), 1);
+
+        let values = -4..=4;
+        for (first, last, a, b) in
+            itertools::iproduct!(values.clone(), values.clone(), values.clone(), values)
+        {
+            let range = SensorRange::new(first, last);
+            let cells = (first..=last).filter(|x| (a..=b).contains(x)).count() as u64;
+            match range.clamp(a, b) {
+                Some(clamped) => {
+                    assert!(a <= clamped.first() && clamped.first() <= clamped.last());
+                    assert!(clamped.last() <= b);
+                    assert_eq!(clamped.len(), cells);
+                }
+                None => assert_eq!(cells, 0),
+            }
+        }
+    }
+
+    #[test]
+    fn join_all() {
+        assert_eq!(
+            SensorRange::join_all([
+                SensorRange::new(10, 20),
+                SensorRange::new(15, 23),
+                SensorRange::new(25, 30),
+            ])
+            .collect::<Vec<_>>(),
+            vec![SensorRange::new(10, 23), SensorRange::new(25, 30),]
+        )
+    }
+}
@@ -1,11 +1,191 @@
+use std::process::{ExitCode, Termination};
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("{0}")]
     Input(#[from] crate::input::InputError),
     #[error("{0}")]
     Parsing(anyhow::Error),
+    #[error("line {line}: {source}")]
+    ParsingAt { line: usize, source: anyhow::Error },
     #[error("{0}")]
     Semantic(anyhow::Error),
 }
 
+impl Error {
+    /// Parsing error for line number `line` of the input.
+    pub fn parsing_at<E: Into<anyhow::Error>>(line: usize, error: E) -> Error {
+        Error::ParsingAt {
+            line,
+            source: error.into(),
+        }
+    }
+
+    pub fn is_input(&self) -> bool {
+        matches!(self, Error::Input(_))
+    }
+
+    pub fn is_parsing(&self) -> bool {
+        matches!(self, Error::Parsing(_) | Error::ParsingAt { .. })
+    }
+
+    pub fn is_semantic(&self) -> bool {
+        matches!(self, Error::Semantic(_))
+    }
+
+    pub fn as_input_error(&self) -> Option<&crate::input::InputError> {
+        match self {
+            Error::Input(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// Status for the process to exit with: 2 for the input, even when a day
+    /// wrapped the error in its own, 3 for parsing and 4 for semantic errors.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Error::Input(_) => 2,
+            Error::Parsing(e) | Error::ParsingAt { source: e, .. } | Error::Semantic(e)
+                if e.chain()
+                    .any(|cause| cause.is::<crate::input::InputError>()) =>
+            {
+                2
+            }
+            Error::Parsing(_) | Error::ParsingAt { .. } => 3,
+            Error::Semantic(_) => 4,
+        }
+    }
+}
+
 pub type Result<T> = core::result::Result<T, Error>;
+
+/// What `main` of a day returns, printing an error on a single line and
+/// exiting with its [`Error::exit_code`].
+#[derive(Debug)]
+pub struct Exit(pub Result<()>);
+impl From<Result<()>> for Exit {
+    fn from(value: Result<()>) -> Self {
+        Exit(value)
+    }
+}
+impl Termination for Exit {
+    fn report(self) -> ExitCode {
+        match self.0 {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("Error: {e}");
+                ExitCode::from(e.exit_code())
+            }
+        }
+    }
+}
+
+/// Annotates an [`Error`] with what was being done when it happened.
+pub trait ErrorContext {
+    type Output;
+
+    /// Prepends `msg` to the error, as `"{msg}: {error}"`.
+    fn context<S: Into<String>>(self, msg: S) -> Self::Output;
+}
+impl ErrorContext for Error {
+    type Output = Error;
+
+    fn context<S: Into<String>>(self, msg: S) -> Error {
+        Error::Semantic(anyhow::anyhow!("{}: {self}", msg.into()))
+    }
+}
+impl<T> ErrorContext for Result<T> {
+    type Output = Result<T>;
+
+    fn context<S: Into<String>>(self, msg: S) -> Result<T> {
+        self.map_err(|e| e.context(msg))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input_error() -> Error {
+        crate::input::input_from_path("input/missing")
+            .next()
+            .unwrap()
+            .unwrap_err()
+            .into()
+    }
+
+    fn parsing_error() -> Error {
+        let params = crate::Params::default().with("row", Some("x"));
+        params.parse::<u32>("row").unwrap_err().into()
+    }
+
+    #[test]
+    fn is_input() {
+        assert!(input_error().is_input());
+        assert!(!parsing_error().is_input());
+    }
+
+    #[test]
+    fn is_parsing() {
+        assert!(parsing_error().is_parsing());
+        assert!(!input_error().is_parsing());
+    }
+
+    #[test]
+    fn parsing_at() {
+        let error = Error::parsing_at(1432, anyhow::anyhow!("Invalid direction \"Q\""));
+        assert_eq!(error.to_string(), "line 1432: Invalid direction \"Q\"");
+        assert!(error.is_parsing());
+        assert_eq!(error.exit_code(), 3);
+    }
+
+    #[test]
+    fn is_semantic() {
+        let error = parsing_error().context("Reading the row");
+        assert!(error.is_semantic());
+        assert!(!parsing_error().is_semantic());
+        assert!(!input_error().is_semantic());
+    }
+
+    #[test]
+    fn as_input_error() {
+        let error = input_error();
+        let input = error.as_input_error().unwrap();
+        assert_eq!(input.path, std::path::Path::new("input/missing"));
+        assert!(parsing_error().as_input_error().is_none());
+    }
+
+    #[test]
+    fn exit_codes() {
+        assert_eq!(input_error().exit_code(), 2);
+        assert_eq!(parsing_error().exit_code(), 3);
+        assert_eq!(parsing_error().context("Row").exit_code(), 4);
+
+        #[derive(thiserror::Error, Debug)]
+        #[error("{0}")]
+        struct DayError(#[from] crate::input::InputError);
+        let Error::Input(input) = input_error() else {
+            unreachable!()
+        };
+        assert_eq!(Error::Parsing(DayError(input).into()).exit_code(), 2);
+
+        assert_eq!(Exit(Ok(())).report(), ExitCode::SUCCESS);
+        assert_eq!(Exit(Err(input_error())).report(), ExitCode::from(2));
+    }
+
+    #[test]
+    fn context() {
+        let error = Error::Parsing(anyhow::anyhow!("Bad number \"x\""));
+        let error = error.context("Reading sensor 3");
+        assert!(matches!(error, Error::Semantic(_)));
+        assert_eq!(error.to_string(), "Reading sensor 3: Bad number \"x\"");
+
+        let result: Result<u32> = Err(error);
+        let result = result.context(format!("Day {}", 15));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Day 15: Reading sensor 3: Bad number \"x\""
+        );
+        assert_eq!(Ok(3).context("Unused").unwrap(), 3);
+    }
+}
@@ -0,0 +1,181 @@
+use std::ops::{Index, IndexMut};
+
+/// Position in a [`Grid`] as `(x, y)`, `y` growing downwards.
+pub type Coord = (usize, usize);
+
+/// Which cells around a cell are its neighbors.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Adjacency {
+    #[default]
+    Orthogonal,
+    /// Orthogonal and diagonal.
+    All,
+}
+impl Adjacency {
+    fn offsets(self) -> &'static [(isize, isize)] {
+        match self {
+            Adjacency::Orthogonal => &[(-1, 0), (1, 0), (0, -1), (0, 1)],
+            Adjacency::All => &[
+                (-1, 0),
+                (1, 0),
+                (0, -1),
+                (0, 1),
+                (-1, -1),
+                (1, -1),
+                (-1, 1),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+/// Rectangular grid of cells stored line by line.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    cells: Vec<T>,
+}
+impl<T> Grid<T> {
+    /// Panics unless `cells` is made of whole lines of `width` cells.
+    pub fn new(width: usize, cells: Vec<T>) -> Self {
+        assert!(
+            cells.len().is_multiple_of(width.max(1)) && (width > 0 || cells.is_empty()),
+            "{} cells can't be split in lines of {width}",
+            cells.len()
+        );
+
+        Grid { width, cells }
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid::new(width, vec![value; width * height])
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len() / self.width.max(1)
+    }
+
+    pub fn contains(&self, (x, y): Coord) -> bool {
+        x < self.width() && y < self.height()
+    }
+
+    pub fn get(&self, coord: Coord) -> Option<&T> {
+        let index = self.index(coord)?;
+        Some(&self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, coord: Coord) -> Option<&mut T> {
+        let index = self.index(coord)?;
+        Some(&mut self.cells[index])
+    }
+
+    pub fn line(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// Every coordinate of the grid in reading order.
+    pub fn coords(&self) -> impl Iterator<Item = Coord> {
+        let width = self.width();
+        (0..self.height()).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Neighbors of `coord` that are inside the grid.
+    pub fn neighbors(&self, (x, y): Coord, adjacency: Adjacency) -> impl Iterator<Item = Coord> {
+        let (width, height) = (self.width(), self.height());
+
+        adjacency
+            .offsets()
+            .iter()
+            .map(move |&(dx, dy)| (x.wrapping_add_signed(dx), y.wrapping_add_signed(dy)))
+            .filter(move |&(x, y)| x < width && y < height)
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            width: self.width,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    fn index(&self, (x, y): Coord) -> Option<usize> {
+        self.contains((x, y)).then(|| y * self.width + x)
+    }
+}
+impl<T> Index<Coord> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, coord: Coord) -> &Self::Output {
+        self.get(coord)
+            .unwrap_or_else(|| panic!("{coord:?} is outside of the grid"))
+    }
+}
+impl<T> IndexMut<Coord> for Grid<T> {
+    fn index_mut(&mut self, coord: Coord) -> &mut Self::Output {
+        self.get_mut(coord)
+            .unwrap_or_else(|| panic!("{coord:?} is outside of the grid"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbors() {
+        let grid = Grid::new(3, (0..9).collect());
+        let neighbors = |coord| {
+            let mut neighbors = grid
+                .neighbors(coord, Adjacency::Orthogonal)
+                .collect::<Vec<_>>();
+            neighbors.sort();
+            neighbors
+        };
+
+        assert_eq!(neighbors((0, 0)), vec![(0, 1), (1, 0)]);
+        assert_eq!(neighbors((2, 0)), vec![(1, 0), (2, 1)]);
+        assert_eq!(neighbors((0, 2)), vec![(0, 1), (1, 2)]);
+        assert_eq!(neighbors((2, 2)), vec![(1, 2), (2, 1)]);
+        assert_eq!(neighbors((1, 0)), vec![(0, 0), (1, 1), (2, 0)]);
+        assert_eq!(neighbors((0, 1)), vec![(0, 0), (0, 2), (1, 1)]);
+        assert_eq!(neighbors((2, 1)), vec![(1, 1), (2, 0), (2, 2)]);
+        assert_eq!(neighbors((1, 2)), vec![(0, 2), (1, 1), (2, 2)]);
+        assert_eq!(neighbors((1, 1)), vec![(0, 1), (1, 0), (1, 2), (2, 1)]);
+
+        assert_eq!(grid.neighbors((0, 0), Adjacency::All).count(), 3);
+        assert_eq!(grid.neighbors((1, 0), Adjacency::All).count(), 5);
+        assert_eq!(grid.neighbors((1, 1), Adjacency::All).count(), 8);
+    }
+
+    #[test]
+    fn get() {
+        let mut grid = Grid::new(3, (0..6).collect());
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get((2, 1)), Some(&5));
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.get((0, 2)), None);
+        assert_eq!(grid.get((usize::MAX, 0)), None);
+        assert_eq!(grid.line(1), &[3, 4, 5]);
+
+        grid[(0, 1)] = 10;
+        assert_eq!(grid.map(|x| x * 2)[(0, 1)], 20);
+        assert_eq!(grid.coords().nth(4), Some((1, 1)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn ragged() {
+        Grid::new(4, vec![0; 6]);
+    }
+}
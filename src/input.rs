@@ -1,33 +1,261 @@
 use std::{
     fs::File,
-    io::{self, BufRead, BufReader, Lines},
+    io::{self, BufRead, BufReader, Lines, Write},
+    marker::PhantomData,
     path::{Path, PathBuf},
+    process::{Command, Stdio},
+    str::FromStr,
 };
 
 pub fn input(day: u32, example: bool) -> InputImpl {
-    InputImpl(input_impl(day, example))
+    input_n(day, 1, example)
 }
 
-fn input_impl(day: u32, example: bool) -> InputResult<InputInner> {
-    let bin = format!("d{day:02}");
+/// Input variant `n` of `day`, in a file with a `_n` suffix, e.g.
+/// `input/examples/d15_2`, unless `n` is 1.
+pub fn input_n(day: u32, n: u32, example: bool) -> InputImpl {
+    InputImpl(input_impl(day, n, example))
+}
+
+pub fn input_from_path<P: AsRef<Path>>(path: P) -> InputImpl {
+    InputImpl(open(path.as_ref().to_path_buf()))
+}
+
+/// Input made of the lines read from `reader`, such as a
+/// `Cursor<Vec<u8>>` or a decompressing stream.
+pub fn input_from_reader<R: BufRead + 'static>(reader: R) -> InputImpl {
+    InputImpl(Ok(InputInner::new(Box::new(reader), PathBuf::new())))
+}
+
+/// Input made of the lines of `s`, ended by `\n` or `\r\n`.
+pub fn input_from_str(s: &str) -> InputImpl {
+    input_from_reader(io::Cursor::new(s.to_string()))
+}
+
+/// Input made of the given `lines`, as if they were the lines of a file, each
+/// ended by `\n` so that a trailing empty line is kept.
+pub fn input_from_lines(lines: &[&str]) -> InputImpl {
+    input_from_str(
+        &lines
+            .iter()
+            .map(|line| format!("{line}\n"))
+            .collect::<String>(),
+    )
+}
+
+/// Full input of `day`, downloaded first from adventofcode.com with the
+/// session cookie in `AOC_SESSION` unless already there.
+pub fn input_fetching(day: u32) -> InputImpl {
+    let path = input_path(day, 1, false);
+    let session = std::env::var("AOC_SESSION").ok();
+    InputImpl(fetch_missing(day, &path, session.as_deref()).and_then(|()| open(path)))
+}
+
+fn input_impl(day: u32, n: u32, example: bool) -> InputResult<InputInner> {
+    open(input_path(day, n, example))
+}
+
+fn input_path(day: u32, n: u32, example: bool) -> PathBuf {
+    let bin = match n {
+        1 => format!("d{day:02}"),
+        n => format!("d{day:02}_{n}"),
+    };
 
     let input_folder = match example {
         true => Path::new("input/examples/"),
         false => Path::new("input/full/"),
     };
 
-    let input_path = input_folder.join(bin);
-    let input_path2 = input_path.clone();
+    input_folder.join(bin)
+}
 
-    let input =
-        BufReader::new(File::open(&input_path).map_err(|e| InputError(input_path, e))?).lines();
-    Ok(InputInner(input, input_path2))
+/// Downloads the input of `day` to `path`, unless there is a file there
+/// already.
+fn fetch_missing(day: u32, path: &Path, session: Option<&str>) -> InputResult<()> {
+    if path.exists() {
+        return Ok(());
+    }
+    let session = session
+        .ok_or(FetchError::MissingToken)
+        .map_err(|e| e.at(path))?;
+    download(day, path, session).map_err(|e| e.at(path))
+}
+
+fn download(day: u32, path: &Path, session: &str) -> Result<(), FetchError> {
+    let url = format!("https://adventofcode.com/2022/day/{day}/input");
+    download_from(&url, path, session)
+}
+
+/// Downloads with curl, to not pull an HTTP client in for a single request.
+fn download_from(url: &str, path: &Path, session: &str) -> Result<(), FetchError> {
+    let http = |e: io::Error| FetchError::Http(e.to_string());
+    if let Some(folder) = path.parent() {
+        std::fs::create_dir_all(folder).map_err(http)?;
+    }
+    let partial = path.with_extension("part");
+
+    // The cookie goes through stdin, as anyone may read the arguments.
+    let mut curl = Command::new("curl")
+        .args(["--silent", "--show-error", "--write-out", "%{http_code}"])
+        .args(["--header", "@-"])
+        .arg("--output")
+        .arg(&partial)
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(http)?;
+    if let Some(mut stdin) = curl.stdin.take() {
+        writeln!(stdin, "Cookie: session={session}").map_err(http)?;
+    }
+    let output = curl.wait_with_output().map_err(http)?;
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&partial);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(FetchError::Http(stderr.trim().to_string()));
+    }
+
+    let status = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if status != "200" {
+        let body = std::fs::read_to_string(&partial).unwrap_or_default();
+        let _ = std::fs::remove_file(&partial);
+        return Err(FetchError::Response {
+            status,
+            body: body.trim().to_string(),
+        });
+    }
+
+    std::fs::rename(&partial, path).map_err(http)
+}
+
+fn open(input_path: PathBuf) -> InputResult<InputInner> {
+    let file = File::open(&input_path).map_err(|error| InputError {
+        path: input_path.clone(),
+        line: 0,
+        error,
+    })?;
+    Ok(InputInner::new(Box::new(BufReader::new(file)), input_path))
 }
 
 impl<S: Iterator<Item = InputResult<String>>> Input for S {}
-pub trait Input: Iterator<Item = InputResult<String>> {}
+pub trait Input: Iterator<Item = InputResult<String>> {
+    /// The lines paired with their numbers, counting from 1 at the next line.
+    /// Reading the input in phases, take them `by_ref` from the numbered
+    /// input so that the later ones keep counting.
+    fn numbered(self) -> Numbered<Self>
+    where
+        Self: Sized,
+    {
+        Numbered {
+            input: self,
+            line: 0,
+        }
+    }
+
+    /// The lines grouped in blocks separated by blank lines. Several blank
+    /// lines in a row, or at either end, separate nothing.
+    fn blocks(self) -> Blocks<Self>
+    where
+        Self: Sized,
+    {
+        Blocks { input: self }
+    }
+
+    /// Each line parsed as a `T`, one at a time as they are read.
+    fn parsed<T: FromStr>(self) -> Parsed<Self, T>
+    where
+        Self: Sized,
+    {
+        Parsed {
+            lines: self.numbered(),
+            parsed: PhantomData,
+        }
+    }
+}
+
+/// Lines of an input with their numbers, from [`Input::numbered`].
+pub struct Numbered<I> {
+    input: I,
+    /// Number of the last line read.
+    line: usize,
+}
+impl<I: Input> Iterator for Numbered<I> {
+    type Item = InputResult<(usize, String)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = self.input.next()?;
+        self.line += 1;
+        Some(line.map(|line| (self.line, line)))
+    }
+}
+
+/// Blocks of lines of an input, from [`Input::blocks`].
+pub struct Blocks<I> {
+    input: I,
+}
+impl<I: Input> Iterator for Blocks<I> {
+    type Item = InputResult<Vec<String>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut block = Vec::new();
+        for line in self.input.by_ref() {
+            match line {
+                Ok(line) if line.is_empty() && block.is_empty() => continue,
+                Ok(line) if line.is_empty() => break,
+                Ok(line) => block.push(line),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        (!block.is_empty()).then_some(Ok(block))
+    }
+}
+
+/// Lines of an input parsed as `T`, from [`Input::parsed`].
+pub struct Parsed<I, T> {
+    lines: Numbered<I>,
+    parsed: PhantomData<fn() -> T>,
+}
+impl<I: Input, T: FromStr> Iterator for Parsed<I, T> {
+    type Item = Result<T, ParseLineError<T::Err>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (line, text) = match self.lines.next()? {
+            Ok(line) => line,
+            Err(e) => return Some(Err(e.into())),
+        };
+        Some(
+            text.parse()
+                .map_err(|error| ParseLineError::Parse { line, text, error }),
+        )
+    }
+}
+
+/// Failure to read a line of [`Parsed`], or to parse it, with its number,
+/// starting from 1, and text.
+#[derive(thiserror::Error, Debug)]
+pub enum ParseLineError<E> {
+    #[error("{0}")]
+    Input(#[from] InputError),
+    #[error("line {line}: {error}")]
+    Parse { line: usize, text: String, error: E },
+}
+impl<E: std::error::Error + Send + Sync + 'static> From<ParseLineError<E>> for crate::Error {
+    fn from(value: ParseLineError<E>) -> Self {
+        match value {
+            ParseLineError::Input(e) => e.into(),
+            ParseLineError::Parse { line, error, .. } => crate::Error::parsing_at(line, error),
+        }
+    }
+}
 
 pub struct InputImpl(InputResult<InputInner>);
+impl InputImpl {
+    /// The failure to open this input, if it couldn't be.
+    pub fn open_error(&self) -> Option<&InputError> {
+        self.0.as_ref().err()
+    }
+}
 impl Iterator for InputImpl {
     type Item = InputResult<String>;
 
@@ -35,8 +263,11 @@ impl Iterator for InputImpl {
         match &mut self.0 {
             Ok(input) => input.next(),
             Err(e) => {
-                let mut consumed =
-                    InputError(e.0.clone(), io::Error::new(e.1.kind(), e.to_string()));
+                let mut consumed = InputError {
+                    path: e.path.clone(),
+                    line: e.line,
+                    error: io::Error::new(e.error.kind(), e.error.to_string()),
+                };
                 std::mem::swap(e, &mut consumed);
                 Some(Err(consumed))
             }
@@ -44,18 +275,289 @@ impl Iterator for InputImpl {
     }
 }
 
-pub struct InputInner(Lines<BufReader<File>>, PathBuf);
+pub struct InputInner {
+    lines: Lines<Box<dyn BufRead>>,
+    /// File read from, empty for other readers.
+    path: PathBuf,
+    /// Number of the last line read.
+    line: u32,
+}
+impl InputInner {
+    fn new(reader: Box<dyn BufRead>, path: PathBuf) -> InputInner {
+        InputInner {
+            lines: reader.lines(),
+            path,
+            line: 0,
+        }
+    }
+}
 impl Iterator for InputInner {
     type Item = InputResult<String>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.0
-            .next()
-            .map(|r| r.map_err(|e| InputError(self.1.clone(), e)))
+        let next = self.lines.next()?;
+        self.line += 1;
+        Some(match next {
+            Ok(s) => Ok(strip_cr(s)),
+            Err(error) => Err(InputError {
+                path: self.path.clone(),
+                line: self.line,
+                error,
+            }),
+        })
+    }
+}
+
+/// Drops the `\r` left over by `\r\n` line endings.
+fn strip_cr(mut s: String) -> String {
+    if s.ends_with('\r') {
+        s.pop();
     }
+    s
 }
 
+/// Failure to open the input, when `line` is 0, or to read its `line`.
 #[derive(thiserror::Error, Debug)]
-#[error("Error opening input {0}: {1}")]
-pub struct InputError(PathBuf, io::Error);
+#[error("{}: {error}", self.context())]
+pub struct InputError {
+    pub path: PathBuf,
+    pub line: u32,
+    error: io::Error,
+}
+impl InputError {
+    /// Whether the input failed to open for not existing.
+    pub fn is_missing(&self) -> bool {
+        self.line == 0 && self.error.kind() == io::ErrorKind::NotFound
+    }
+
+    fn context(&self) -> String {
+        match self.line {
+            0 => format!("Error opening input {}", self.path.display()),
+            line if self.path.as_os_str().is_empty() => format!("Error on line {line} of input"),
+            line => format!("Error on line {line} of input {}", self.path.display()),
+        }
+    }
+}
 pub type InputResult<T> = Result<T, InputError>;
+
+/// Failure to download an input, reported as an [`InputError`] opening it.
+#[derive(thiserror::Error, Debug)]
+pub enum FetchError {
+    #[error("Missing the AOC_SESSION cookie to download it with")]
+    MissingToken,
+    #[error("Download failed: {0}")]
+    Http(String),
+    #[error("Download answered {status}: {body}")]
+    Response { status: String, body: String },
+}
+impl FetchError {
+    fn at(self, path: &Path) -> InputError {
+        InputError {
+            path: path.to_path_buf(),
+            line: 0,
+            error: io::Error::other(self),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_path() {
+        let lines = input_from_path("input/examples/d01").collect::<Result<Vec<_>, _>>();
+        let expected = input(1, true).collect::<Result<Vec<_>, _>>();
+        assert_eq!(lines.unwrap(), expected.unwrap());
+
+        let mut missing = input_from_path("input/missing");
+        assert!(missing.open_error().is_some_and(InputError::is_missing));
+        let error = missing.next().unwrap().unwrap_err();
+        assert_eq!(error.path, Path::new("input/missing"));
+        assert_eq!(error.line, 0);
+        assert!(error.is_missing());
+        assert!(input(1, true).open_error().is_none());
+        assert!(error
+            .to_string()
+            .starts_with("Error opening input input/missing: "));
+    }
+
+    #[test]
+    fn variants() {
+        let first = input_n(1, 1, true).collect::<Result<Vec<_>, _>>();
+        let expected = input(1, true).collect::<Result<Vec<_>, _>>();
+        assert_eq!(first.unwrap(), expected.unwrap());
+
+        let error = input_n(1, 2, true).next().unwrap().unwrap_err();
+        assert_eq!(error.path, Path::new("input/examples/d01_2"));
+        let error = input_n(15, 3, false).next().unwrap().unwrap_err();
+        assert_eq!(error.path, Path::new("input/full/d15_3"));
+    }
+
+    #[test]
+    fn fetch_cached() {
+        let cached = Path::new("input/examples/d01");
+        assert!(fetch_missing(1, cached, None).is_ok());
+
+        let missing = std::env::temp_dir().join(format!("aoc-fetch-{}", std::process::id()));
+        let error = fetch_missing(1, &missing, None).unwrap_err();
+        assert_eq!(error.path, missing);
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "Error opening input {}: Missing the AOC_SESSION cookie to download it with",
+                missing.display()
+            )
+        );
+        assert!(!missing.exists());
+    }
+
+    #[test]
+    fn download_response() {
+        let folder = std::env::temp_dir().join(format!("aoc-download-{}", std::process::id()));
+        std::fs::create_dir_all(&folder).unwrap();
+        let page = folder.join("page");
+        std::fs::write(&page, "Please log in\n").unwrap();
+
+        // Files have no HTTP status.
+        let path = folder.join("input");
+        let url = format!("file://{}", page.display());
+        let error = download_from(&url, &path, "token").unwrap_err();
+        assert!(
+            matches!(&error, FetchError::Response { status, body }
+                if status == "000" && body == "Please log in"),
+            "{error:?}"
+        );
+        assert!(!path.exists());
+        assert!(!path.with_extension("part").exists());
+
+        std::fs::remove_dir_all(folder).unwrap();
+    }
+
+    #[test]
+    fn crlf() {
+        let path = std::env::temp_dir().join(format!("aoc-crlf-{}", std::process::id()));
+        std::fs::write(&path, "a\r\nb c\r\n\r\nd\r\n").unwrap();
+        let lines = input_from_path(&path).collect::<Result<Vec<_>, _>>();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(lines.unwrap(), ["a", "b c", "", "d"]);
+    }
+
+    #[test]
+    fn read_error() {
+        let path = std::env::temp_dir().join(format!("aoc-utf8-{}", std::process::id()));
+        std::fs::write(&path, b"a\nb\n\xff\nc\n").unwrap();
+        let lines = input_from_path(&path).collect::<Vec<_>>();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(lines[0].as_ref().unwrap(), "a");
+        assert_eq!(lines[1].as_ref().unwrap(), "b");
+        let error = lines[2].as_ref().unwrap_err();
+        assert_eq!(error.line, 3);
+        assert!(error
+            .to_string()
+            .starts_with(&format!("Error on line 3 of input {}: ", path.display())));
+    }
+
+    #[test]
+    fn from_reader() {
+        let lines = |r| input_from_reader(r).collect::<Vec<_>>();
+
+        let cursor = io::Cursor::new(b"a\r\nb c\n\nd\n".to_vec());
+        let read = lines(cursor).into_iter().map(Result::unwrap);
+        assert_eq!(read.collect::<Vec<_>>(), ["a", "b c", "", "d"]);
+
+        let read = lines(io::Cursor::new(b"a\n\xff\n".to_vec()));
+        assert_eq!(read[0].as_ref().unwrap(), "a");
+        let error = read[1].as_ref().unwrap_err();
+        assert_eq!(error.line, 2);
+        assert!(error.to_string().starts_with("Error on line 2 of input: "));
+    }
+
+    #[test]
+    fn from_str() {
+        let lines = |s| input_from_str(s).map(Result::unwrap).collect::<Vec<_>>();
+
+        assert_eq!(lines("a\nb c\n\nd"), ["a", "b c", "", "d"]);
+        assert_eq!(lines("a\r\nb c\r\n\r\nd\r\n"), ["a", "b c", "", "d"]);
+        assert_eq!(lines("a\n"), ["a"]);
+        assert!(lines("").is_empty());
+
+        let file = std::fs::read_to_string("input/examples/d01").unwrap();
+        let expected = input(1, true).map(Result::unwrap).collect::<Vec<_>>();
+        assert_eq!(lines(&file), expected);
+        assert_eq!(lines(&file.replace('\n', "\r\n")), expected);
+    }
+
+    #[test]
+    fn numbered() {
+        let mut input = input_from_lines(&["a", "", "b", "c"]).numbered();
+        let first = input
+            .by_ref()
+            .map_while(|line| line.ok().filter(|(_, line)| !line.is_empty()))
+            .collect::<Vec<_>>();
+        assert_eq!(first, [(1, "a".to_string())]);
+
+        let rest = input.collect::<InputResult<Vec<_>>>().unwrap();
+        assert_eq!(rest, [(3, "b".to_string()), (4, "c".to_string())]);
+    }
+
+    #[test]
+    fn blocks() {
+        let blocks = |lines: &[&str]| {
+            input_from_lines(lines)
+                .blocks()
+                .collect::<InputResult<Vec<_>>>()
+                .unwrap()
+        };
+        let expected = [vec!["a", "b"], vec!["c"]];
+
+        assert_eq!(blocks(&["a", "b", "", "c"]), expected);
+        assert_eq!(blocks(&["", "", "a", "b", "", "c"]), expected);
+        assert_eq!(blocks(&["a", "b", "", "", "", "c", "", ""]), expected);
+        assert!(blocks(&[]).is_empty());
+        assert!(blocks(&["", ""]).is_empty());
+
+        let reader = io::Cursor::new(b"a\nb\xff\nc\n\nd\n".to_vec());
+        let mut blocks = input_from_reader(reader).blocks();
+        assert_eq!(blocks.next().unwrap().unwrap_err().line, 2);
+        assert_eq!(blocks.next().unwrap().unwrap(), ["c"]);
+        assert_eq!(blocks.next().unwrap().unwrap(), ["d"]);
+        assert!(blocks.next().is_none());
+    }
+
+    #[test]
+    fn parsed() {
+        let numbers = input_from_lines(&["1", "2", "x", "4"])
+            .parsed::<u32>()
+            .collect::<Vec<_>>();
+        assert_eq!(numbers.len(), 4);
+        assert_eq!(numbers[1].as_ref().unwrap(), &2);
+        let Err(ParseLineError::Parse { line, text, .. }) = &numbers[2] else {
+            panic!("{:?} parsed", numbers[2]);
+        };
+        assert_eq!((*line, text.as_str()), (3, "x"));
+
+        let error = crate::Error::from(numbers.into_iter().nth(2).unwrap().unwrap_err());
+        assert_eq!(error.to_string(), "line 3: invalid digit found in string");
+
+        let missing = input_from_path("input/missing").parsed::<u32>().next();
+        assert!(matches!(missing, Some(Err(ParseLineError::Input(_)))));
+    }
+
+    #[test]
+    fn from_lines() {
+        let lines = |l| input_from_lines(l).map(Result::unwrap).collect::<Vec<_>>();
+
+        assert_eq!(lines(&["a", "b c", "", "d"]), ["a", "b c", "", "d"]);
+        assert_eq!(lines(&["a", ""]), ["a", ""]);
+        assert_eq!(lines(&[""]), [""]);
+        assert!(lines(&[]).is_empty());
+
+        let file = std::fs::read_to_string("input/examples/d01").unwrap();
+        let file = file.lines().collect::<Vec<_>>();
+        let expected = input(1, true).map(Result::unwrap).collect::<Vec<_>>();
+        assert_eq!(lines(&file), expected);
+    }
+}
@@ -1,34 +1,838 @@
+pub mod answers;
+pub mod days;
 pub mod error;
+pub mod grid;
 pub mod input;
+pub mod nested;
+pub mod params;
+pub mod pgm;
+pub mod search;
 
 pub use error::Error;
+pub use error::Exit;
 pub use error::Result;
 pub use input::input;
+pub use params::{Params, Parts};
+
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    time::{Duration, Instant},
+};
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Answer<T = u32, T2 = T> {
     pub part1: T,
     pub part2: T2,
 }
+impl<T, T2> Answer<T, T2> {
+    pub fn map_part1<U, F: FnOnce(T) -> U>(self, f: F) -> Answer<U, T2> {
+        Answer {
+            part1: f(self.part1),
+            part2: self.part2,
+        }
+    }
+
+    pub fn map_part2<U, F: FnOnce(T2) -> U>(self, f: F) -> Answer<T, U> {
+        Answer {
+            part1: self.part1,
+            part2: f(self.part2),
+        }
+    }
+
+    pub fn map<U, V, F: FnOnce(T) -> U, G: FnOnce(T2) -> V>(self, f: F, g: G) -> Answer<U, V> {
+        Answer {
+            part1: f(self.part1),
+            part2: g(self.part2),
+        }
+    }
+
+    /// Pairs the parts of two answers, e.g. `Answer::zip(before, after)`.
+    pub fn zip<U, U2>(self, other: Answer<U, U2>) -> Answer<(T, U), (T2, U2)> {
+        Answer {
+            part1: (self.part1, other.part1),
+            part2: (self.part2, other.part2),
+        }
+    }
+
+    /// Panics telling which parts differ unless the answer is `expected`.
+    pub fn assert_eq(self, expected: &Answer<T, T2>) -> Answer<T, T2>
+    where
+        T: PartialEq + std::fmt::Debug,
+        T2: PartialEq + std::fmt::Debug,
+    {
+        let mut wrong = Vec::new();
+        if self.part1 != expected.part1 {
+            wrong.push(format!(
+                "part 1 is {:?}, expected {:?}",
+                self.part1, expected.part1
+            ));
+        }
+        if self.part2 != expected.part2 {
+            wrong.push(format!(
+                "part 2 is {:?}, expected {:?}",
+                self.part2, expected.part2
+            ));
+        }
+        assert!(wrong.is_empty(), "Wrong answer, {}", wrong.join(", "));
+        self
+    }
 
-fn cli_run_example() -> bool {
-    let mut example = false;
-    for arg in std::env::args().skip(1) {
-        match arg.as_str() {
-            "-e" => example = true,
-            x => panic!("{x:?} is not a recognized CLI switch"),
+    /// The answer with a value beyond the two parts, printed after them as
+    /// `label: value`.
+    pub fn with_extra(self, label: &str, value: impl std::fmt::Display) -> Extended<Self> {
+        Extended::from(self).with_extra(label, value)
+    }
+}
+impl<T, T2> From<(T, T2)> for Answer<T, T2> {
+    fn from((part1, part2): (T, T2)) -> Self {
+        Answer { part1, part2 }
+    }
+}
+impl<T, T2> From<Answer<T, T2>> for (T, T2) {
+    fn from(answer: Answer<T, T2>) -> Self {
+        (answer.part1, answer.part2)
+    }
+}
+/// Prints a part after its label, or on the lines below when it takes
+/// several, like a screen.
+fn write_part<T: std::fmt::Display>(
+    f: &mut std::fmt::Formatter<'_>,
+    number: u32,
+    part: &T,
+) -> std::fmt::Result {
+    let part = part.to_string();
+    match part.contains('\n') {
+        true => write!(f, "Part {number}:\n{}", part.trim_start_matches('\n')),
+        false => write!(f, "Part {number}: {part}"),
+    }
+}
+impl<T: std::fmt::Display, T2: std::fmt::Display> std::fmt::Display for Answer<T, T2> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_part(f, 1, &self.part1)?;
+        writeln!(f)?;
+        write_part(f, 2, &self.part2)
+    }
+}
+
+/// An answer with labelled values beyond its parts, from
+/// [`Answer::with_extra`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct Extended<A> {
+    pub answer: A,
+    pub extras: Vec<(String, String)>,
+}
+impl<A> Extended<A> {
+    pub fn with_extra(mut self, label: &str, value: impl std::fmt::Display) -> Self {
+        self.extras.push((label.to_string(), value.to_string()));
+        self
+    }
+}
+impl<A> From<A> for Extended<A> {
+    fn from(answer: A) -> Self {
+        Extended {
+            answer,
+            extras: Vec::new(),
+        }
+    }
+}
+impl<A: std::fmt::Display> std::fmt::Display for Extended<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.answer)?;
+        for (label, value) in &self.extras {
+            write!(f, "\n{label}: {value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Answer to a part of the puzzle, unless it was not selected.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(untagged)
+)]
+pub enum Part<T> {
+    Solved(T),
+    Skipped,
+}
+impl<T> Part<T> {
+    fn solve<E, F: FnOnce() -> std::result::Result<T, E>>(
+        selected: bool,
+        solve: F,
+    ) -> std::result::Result<Part<T>, E> {
+        match selected {
+            true => solve().map(Part::Solved),
+            false => Ok(Part::Skipped),
+        }
+    }
+
+    /// The answer, panicking if the part was skipped.
+    pub fn unwrap(self) -> T {
+        match self {
+            Part::Solved(value) => value,
+            Part::Skipped => panic!("Part was skipped"),
+        }
+    }
+}
+impl<T: std::fmt::Display> std::fmt::Display for Part<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::Solved(value) => value.fmt(f),
+            Part::Skipped => f.write_str("skipped"),
+        }
+    }
+}
+impl<T: std::fmt::Debug> std::fmt::Debug for Part<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::Solved(value) => value.fmt(f),
+            Part::Skipped => f.write_str("skipped"),
         }
     }
-    example
 }
 
+/// Switches given on the command line, failing on one not recognized.
+pub fn params() -> Result<Params> {
+    Ok(Params::try_from_args(std::env::args().skip(1))?)
+}
+
+/// The input named on the command line, the day's example, in the variant
+/// picked with `-e2`, `-e3`..., or full input otherwise, downloaded first if
+/// missing with `--fetch`.
+fn cli_input(day: u32, params: &Params) -> input::InputImpl {
+    match params.input() {
+        Some(path) => input::input_from_path(path),
+        None if !params.example() && params.flag("fetch") => input::input_fetching(day),
+        None => input::input_n(day, params.variant(), params.example()),
+    }
+}
+
+/// Day of a binary named like `d12`, given the path it was run as.
+pub fn day_from_bin(bin: &str) -> Option<u32> {
+    let name = std::path::Path::new(bin).file_stem()?.to_str()?;
+    let digits = name.strip_prefix('d')?;
+    match digits.len() == 2 && digits.bytes().all(|b| b.is_ascii_digit()) {
+        true => digits.parse().ok(),
+        false => None,
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Cannot tell the day from the executable name {0:?}, pass it with --day")]
+pub struct UnknownDay(String);
+impl From<UnknownDay> for Error {
+    fn from(value: UnknownDay) -> Self {
+        Error::Parsing(value.into())
+    }
+}
+
+/// The day of the running binary, taken from its name or from `--day`
+/// otherwise.
+fn cli_day() -> Result<u32> {
+    let bin = std::env::args().next().unwrap_or_default();
+    match day_from_bin(&bin) {
+        Some(day) => Ok(day),
+        None => params()?.parse("day")?.ok_or(UnknownDay(bin).into()),
+    }
+}
+
+/// The answer of `day` to the input `params` picks, as `-i`, `-e`, `-eN` and
+/// `--fetch` do on the command line, returned rather than printed, for
+/// running days from code.
+pub fn run_day<A, F>(day: u32, params: &Params, answer: F) -> Result<A>
+where
+    F: FnOnce(input::InputImpl) -> Result<A>,
+{
+    answer(cli_input(day, params))
+}
+
+/// Like [`main_impl`], with the day told by [`cli_day`].
+pub fn main_impl_auto<T, T2, F>(answer: F) -> Result<()>
+where
+    T: std::fmt::Display,
+    T2: std::fmt::Display,
+    F: Fn(input::InputImpl) -> Result<Answer<T, T2>>,
+{
+    main_impl(cli_day()?, answer)
+}
+
+/// Prints the answer of `day`, hiding the parts not selected on the command
+/// line. Use [`main_impl_parts`] to not solve them at all, or to time them
+/// apart, and [`run_day`] to get the answer instead.
 pub fn main_impl<T, T2, F>(day: u32, answer: F) -> Result<()>
 where
-    T: std::fmt::Debug,
-    T2: std::fmt::Debug,
-    F: FnOnce(input::InputImpl) -> Result<Answer<T, T2>>,
+    T: std::fmt::Display,
+    T2: std::fmt::Display,
+    F: Fn(input::InputImpl) -> Result<Answer<T, T2>>,
 {
-    println!("{:#?}", answer(input(day, cli_run_example()))?);
+    main_impl_parts(day, |input, parts| {
+        let Answer { part1, part2 } = answer(input)?;
+        Ok(Answer {
+            part1: Part::solve(parts.part1, || Ok::<_, Error>(part1))?,
+            part2: Part::solve(parts.part2, || Ok::<_, Error>(part2))?,
+        })
+    })
+}
+
+/// Like [`main_impl`], panicking if the answer to the full input is not
+/// `expected`, when given, so that running the day checks it is still right.
+pub fn main_impl_expecting<T, T2, F>(
+    day: u32,
+    expected: Option<Answer<T, T2>>,
+    answer: F,
+) -> Result<()>
+where
+    T: std::fmt::Display + PartialEq + std::fmt::Debug,
+    T2: std::fmt::Display + PartialEq + std::fmt::Debug,
+    F: Fn(input::InputImpl) -> Result<Answer<T, T2>>,
+{
+    let params = params()?;
+    let full = !params.example() && params.input().is_none();
+    let expected = expected.filter(|_| full);
+    main_impl(day, |input| {
+        let answer = answer(input)?;
+        Ok(match &expected {
+            Some(expected) => answer.assert_eq(expected),
+            None => answer,
+        })
+    })
+}
+
+/// Prints the answer of `day`, solving only the parts selected on the
+/// command line. With `-b runs` it solves them that many times and prints
+/// how long it took. With `-c` it checks the answer against the known ones.
+/// Extra values, from [`Answer::with_extra`], are printed after the parts
+/// but left out of `--json` and `-c`. With `-h` it prints the usage instead.
+pub fn main_impl_parts<T, T2, A, F>(day: u32, answer: F) -> Result<()>
+where
+    T: std::fmt::Display,
+    T2: std::fmt::Display,
+    A: Into<Extended<Answer<Part<T>, Part<T2>>>>,
+    F: Fn(input::InputImpl, Parts) -> Result<A>,
+{
+    let answer = |input, parts| answer(input, parts).map(Into::into);
+    let params = params()?;
+    if params.help() {
+        println!("{}", params::USAGE);
+        return Ok(());
+    }
+    let parts = params.parts();
+    let runs = params.bench()?;
+    let (answer, duration) = match runs {
+        Some(runs) => {
+            let parts = Parts {
+                timed: false,
+                ..parts
+            };
+            let (answer, stats) = bench(day, &params, runs, |input| answer(input, parts))?;
+            eprintln!("{stats}");
+            (answer, stats.median)
+        }
+        // Timed once opened, to leave opening the file out of the time taken.
+        None => run_day(day, &params, |input| {
+            let begin = Instant::now();
+            let answer = answer(input, parts)?;
+            Ok((answer, begin.elapsed()))
+        })?,
+    };
+    let json = params.flag("json");
+    match json {
+        true => println!("{}", answer_json(day, &answer.answer, duration)),
+        false => println!("{answer}"),
+    }
+    if parts.timed && runs.is_none() {
+        eprintln!("Total: {}", format_duration(duration));
+    }
+
+    if params.check() {
+        let expected = answers::load(answers::path(params.example()), day)?;
+        let wrong = expected.check(&answer.answer);
+        if !wrong.is_empty() {
+            return Err(answers::WrongAnswer(wrong).into());
+        }
+        if !json {
+            println!("OK");
+        }
+    }
 
     Ok(())
 }
+
+/// `answer` as a JSON object, with the time it took, for `--json`. Integer
+/// parts are numbers, other parts strings of how they print, skipped parts
+/// null.
+fn answer_json<T, T2>(day: u32, answer: &Answer<Part<T>, Part<T2>>, duration: Duration) -> String
+where
+    T: std::fmt::Display,
+    T2: std::fmt::Display,
+{
+    format!(
+        "{{\"day\":{day},\"part1\":{},\"part2\":{},\"duration_ms\":{}}}",
+        json_value(&answer.part1),
+        json_value(&answer.part2),
+        duration.as_secs_f64() * 1e3
+    )
+}
+
+fn json_value<T: std::fmt::Display>(part: &Part<T>) -> String {
+    let Part::Solved(value) = part else {
+        return "null".to_string();
+    };
+    let value = value.to_string();
+    // Only numbers written as JSON writes them, not "+5" or "007".
+    if value.parse::<i128>().is_ok_and(|n| n.to_string() == value) {
+        return value;
+    }
+
+    // Multi-line parts, like a screen, print starting with a new line.
+    let mut json = String::from("\"");
+    for c in value.trim_matches('\n').chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// How long the runs of a benchmark took.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchStats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub max: Duration,
+}
+impl BenchStats {
+    fn new(mut durations: Vec<Duration>) -> BenchStats {
+        durations.sort();
+        let runs = durations.len();
+        BenchStats {
+            runs,
+            min: durations[0],
+            median: durations[runs / 2],
+            mean: durations.iter().sum::<Duration>() / runs as u32,
+            max: durations[runs - 1],
+        }
+    }
+}
+impl std::fmt::Display for BenchStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} runs: min {}, median {}, mean {}, max {}",
+            self.runs,
+            format_duration(self.min),
+            format_duration(self.median),
+            format_duration(self.mean),
+            format_duration(self.max)
+        )
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Run {run} answered {answer}, unlike the first run {first}")]
+pub struct UnstableAnswer {
+    pub run: u32,
+    pub answer: String,
+    pub first: String,
+}
+impl From<UnstableAnswer> for Error {
+    fn from(value: UnstableAnswer) -> Self {
+        Error::Semantic(value.into())
+    }
+}
+
+/// Runs `answer` `runs` times on the input of `day` picked by `params`, as
+/// [`run_day`] does, opening it again for every run but timing only the
+/// answer. Every run must give the same answer, the last one is returned.
+/// Panics if `runs` is 0, which [`Params::bench`] refuses.
+pub fn bench<A, F>(day: u32, params: &Params, runs: u32, answer: F) -> Result<(A, BenchStats)>
+where
+    A: std::fmt::Display,
+    F: Fn(input::InputImpl) -> Result<A>,
+{
+    assert!(runs > 0, "Benchmark without any run");
+    let mut durations = Vec::new();
+    let mut first = None::<String>;
+    let mut last = None;
+
+    for run in 1..=runs {
+        let (answer, duration) = run_day(day, params, |input| {
+            let begin = Instant::now();
+            let answer = answer(input)?;
+            Ok((answer, begin.elapsed()))
+        })?;
+        durations.push(duration);
+
+        let printed = answer.to_string();
+        match &first {
+            None => first = Some(printed),
+            Some(first) if *first != printed => {
+                return Err(UnstableAnswer {
+                    run,
+                    answer: printed,
+                    first: first.clone(),
+                }
+                .into())
+            }
+            Some(_) => {}
+        }
+        last = Some(answer);
+    }
+
+    Ok((last.unwrap(), BenchStats::new(durations)))
+}
+
+/// The `n` largest items of `iter`, largest first. Only `n` of them are kept
+/// at a time, in a min-heap.
+pub fn top_n<T: Ord>(iter: impl Iterator<Item = T>, n: usize) -> Vec<T> {
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let mut heap = BinaryHeap::with_capacity(n + 1);
+    for item in iter {
+        heap.push(Reverse(item));
+        if heap.len() > n {
+            heap.pop();
+        }
+    }
+
+    // Ascending in reverse is largest first.
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse(item)| item)
+        .collect()
+}
+
+/// `duration` in µs, ms or s, whichever reads best.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs_f64();
+    match secs {
+        _ if secs < 1e-3 => format!("{:.1}µs", secs * 1e6),
+        _ if secs < 1.0 => format!("{:.1}ms", secs * 1e3),
+        _ => format!("{secs:.2}s"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part() {
+        let answer = Answer {
+            part1: Part::Solved(24),
+            part2: Part::<String>::Skipped,
+        };
+        assert_eq!(
+            format!("{answer:#?}"),
+            "Answer {\n    part1: 24,\n    part2: skipped,\n}"
+        );
+        assert_eq!(answer.to_string(), "Part 1: 24\nPart 2: skipped");
+        assert_eq!(answer.part1.unwrap(), 24);
+    }
+
+    #[test]
+    fn display() {
+        let answer = Answer {
+            part1: 42,
+            part2: "CMZ".to_string(),
+        };
+        assert_eq!(answer.to_string(), "Part 1: 42\nPart 2: CMZ");
+
+        let screen = Answer {
+            part1: 13140,
+            part2: "\n##..\n.##.",
+        };
+        assert_eq!(screen.to_string(), "Part 1: 13140\nPart 2:\n##..\n.##.");
+        let screen = screen.map_part2(|x| x.trim_start());
+        assert_eq!(screen.to_string(), "Part 1: 13140\nPart 2:\n##..\n.##.");
+        assert_eq!(
+            format!("{answer:?}"),
+            "Answer { part1: 42, part2: \"CMZ\" }"
+        );
+    }
+
+    #[test]
+    fn map() {
+        let answer = || Answer {
+            part1: 24000,
+            part2: vec![7, 5],
+        };
+
+        let mapped = answer().map_part1(|x| x / 1000);
+        assert_eq!(
+            mapped,
+            Answer {
+                part1: 24,
+                part2: vec![7, 5]
+            }
+        );
+        let mapped = answer().map_part2(|x| x.len());
+        assert_eq!(
+            mapped,
+            Answer {
+                part1: 24000,
+                part2: 2
+            }
+        );
+        let mapped = answer().map(|x| x.to_string(), |x| format!("{x:?}"));
+        assert_eq!(mapped.to_string(), "Part 1: 24000\nPart 2: [7, 5]");
+
+        let narrow: Answer<u32> = (1, 2).into();
+        let wide = narrow.map(u64::from, u64::from);
+        assert_eq!(
+            wide,
+            Answer {
+                part1: 1u64,
+                part2: 2u64
+            }
+        );
+    }
+
+    #[test]
+    fn zip() {
+        let zipped = Answer::zip(Answer::from((1, "a")), Answer::from((2, 'b')));
+        assert_eq!(
+            zipped,
+            Answer {
+                part1: (1, 2),
+                part2: ("a", 'b')
+            }
+        );
+
+        // Ordered by part 1 first, like the answers zipped.
+        let first = Answer::zip(Answer::from((1, 9)), Answer::from((5, 0)));
+        let second = Answer::zip(Answer::from((2, 0)), Answer::from((0, 0)));
+        assert!(first < second);
+    }
+
+    #[test]
+    fn extras() {
+        let answer = Answer::from((26, 56000011))
+            .with_extra("Row", 10)
+            .with_extra("Limit", 20);
+        assert_eq!(
+            answer.to_string(),
+            "Part 1: 26\nPart 2: 56000011\nRow: 10\nLimit: 20"
+        );
+        assert_eq!(answer.answer, Answer::from((26, 56000011)));
+
+        let plain = Extended::from(Answer::from((1, 2)));
+        assert!(plain.extras.is_empty());
+        assert_eq!(plain.to_string(), "Part 1: 1\nPart 2: 2");
+    }
+
+    #[test]
+    fn tuples() {
+        let answer: Answer<u32, &str> = (24000, "CMZ").into();
+        assert_eq!(
+            answer,
+            Answer {
+                part1: 24000,
+                part2: "CMZ"
+            }
+        );
+        let (part1, part2) = answer.into();
+        assert_eq!((part1, part2), (24000, "CMZ"));
+    }
+
+    #[test]
+    fn json() {
+        let answer = Answer {
+            part1: Part::Solved(13140),
+            part2: Part::Solved("\n#.\n.#".to_string()),
+        };
+        assert_eq!(
+            answer_json(10, &answer, Duration::from_micros(1500)),
+            r##"{"day":10,"part1":13140,"part2":"#.\n.#","duration_ms":1.5}"##
+        );
+
+        let answer = Answer {
+            part1: Part::Solved("say \"hi\"\t\\".to_string()),
+            part2: Part::<u32>::Skipped,
+        };
+        assert_eq!(
+            answer_json(5, &answer, Duration::ZERO),
+            r#"{"day":5,"part1":"say \"hi\"\u0009\\","part2":null,"duration_ms":0}"#
+        );
+
+        let json = |value: &str| json_value(&Part::Solved(value));
+        assert_eq!(json("-42"), "-42");
+        assert_eq!(json("+5"), r#""+5""#);
+        assert_eq!(json("007"), r#""007""#);
+        assert_eq!(json("-0"), r#""-0""#);
+    }
+
+    #[test]
+    fn assert_eq() {
+        let answer = || Answer {
+            part1: 24000,
+            part2: "CMZ",
+        };
+        assert_eq!(answer().assert_eq(&answer()), answer());
+
+        let wrong = std::panic::catch_unwind(|| {
+            answer().assert_eq(&Answer {
+                part1: 24000,
+                part2: "MCD",
+            })
+        });
+        let message = wrong.unwrap_err().downcast::<String>().unwrap();
+        assert_eq!(
+            *message,
+            "Wrong answer, part 2 is \"CMZ\", expected \"MCD\""
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let numbers: Answer<usize> = (95437, 24933642).into();
+        let json = serde_json::to_string(&numbers).unwrap();
+        assert_eq!(json, r#"{"part1":95437,"part2":24933642}"#);
+        assert_eq!(
+            serde_json::from_str::<Answer<usize>>(&json).unwrap(),
+            numbers
+        );
+
+        let strings: Answer<String> = ("CMZ".to_string(), "MCD".to_string()).into();
+        let json = serde_json::to_string(&strings).unwrap();
+        assert_eq!(json, r#"{"part1":"CMZ","part2":"MCD"}"#);
+        assert_eq!(
+            serde_json::from_str::<Answer<String>>(&json).unwrap(),
+            strings
+        );
+
+        let parts: Answer<Part<u32>> = (Part::Solved(24), Part::Skipped).into();
+        let json = serde_json::to_string(&parts).unwrap();
+        assert_eq!(json, r#"{"part1":24,"part2":null}"#);
+        assert_eq!(
+            serde_json::from_str::<Answer<Part<u32>>>(&json).unwrap(),
+            parts
+        );
+    }
+
+    #[test]
+    fn benchmark() {
+        let runs = std::cell::Cell::new(0);
+        let sum = |input: input::InputImpl| {
+            runs.set(runs.get() + 1);
+            input
+                .map(|line| Ok(line?.parse::<u32>().unwrap_or_default()))
+                .sum::<Result<u32>>()
+        };
+
+        let params = |args: &[&str]| Params::from_args(args.iter().map(|x| x.to_string()));
+        let example = params(&["-e"]);
+        let (answer, stats) = bench(1, &example, 5, sum).unwrap();
+        assert_eq!(answer, 55000);
+        assert_eq!(runs.get(), 5);
+        assert_eq!(stats.runs, 5);
+        assert!(stats.min <= stats.median && stats.median <= stats.max);
+        assert!(stats.min <= stats.mean && stats.mean <= stats.max);
+
+        let calls = std::cell::Cell::new(0);
+        let unstable = |input: input::InputImpl| {
+            calls.set(calls.get() + 1);
+            Ok(input.count() + calls.get() / 3)
+        };
+        let error = bench(1, &example, 5, unstable).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Run 3 answered 15, unlike the first run 14"
+        );
+
+        // Each run reads the file again, seeing the line the previous one
+        // added.
+        let path = std::env::temp_dir().join(format!("aoc-bench-{}", std::process::id()));
+        std::fs::write(&path, "1\n").unwrap();
+        let growing = |input: input::InputImpl| {
+            let lines = input.count();
+            let mut file = std::fs::OpenOptions::new()
+                .append(true)
+                .open(&path)
+                .unwrap();
+            std::io::Write::write_all(&mut file, b"1\n").unwrap();
+            Ok(lines)
+        };
+        let file = params(&["-i", path.to_str().unwrap()]);
+        let error = bench(1, &file, 5, growing).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Run 2 answered 2, unlike the first run 1"
+        );
+
+        let error = bench(1, &params(&["-e2"]), 5, sum).unwrap_err();
+        assert!(error.is_input());
+    }
+
+    #[test]
+    fn run() {
+        let elves = |input: input::InputImpl| {
+            let lines = input.collect::<std::result::Result<Vec<_>, _>>()?;
+            let elves = lines.split(String::is_empty).count() as u32;
+            Ok(Answer::from((elves, lines.len() as u32)))
+        };
+        let params = |args: &[&str]| Params::from_args(args.iter().map(|x| x.to_string()));
+        let example = params(&["-e"]);
+        assert_eq!(run_day(1, &example, elves).unwrap(), Answer::from((5, 14)));
+
+        let missing = run_day(25, &example, elves).unwrap_err();
+        assert!(missing.is_input());
+        let variant = run_day(1, &params(&["-e2"]), elves).unwrap_err();
+        assert!(variant.to_string().contains("input/examples/d01_2"));
+
+        let other = params(&["-i", "input/examples/d02"]);
+        assert_eq!(run_day(1, &other, elves).unwrap(), Answer::from((1, 3)));
+    }
+
+    #[test]
+    fn top() {
+        let numbers = [5, 1, 9, 3, 7];
+        assert_eq!(top_n(numbers.into_iter(), 3), [9, 7, 5]);
+        assert_eq!(top_n(numbers.into_iter(), 0), Vec::<i32>::new());
+        assert_eq!(top_n(numbers.into_iter(), 10), [9, 7, 5, 3, 1]);
+        assert_eq!(top_n(std::iter::empty::<i32>(), 2), Vec::<i32>::new());
+        assert_eq!(top_n([4, 8, 4, 8, 4].into_iter(), 3), [8, 8, 4]);
+
+        // Equal keys keep apart by what follows them.
+        let ties = [(7, Reverse(0)), (3, Reverse(1)), (7, Reverse(2))];
+        assert_eq!(
+            top_n(ties.into_iter(), 2),
+            [(7, Reverse(0)), (7, Reverse(2))]
+        );
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(format_duration(Duration::from_nanos(44_250)), "44.2µs");
+        assert_eq!(format_duration(Duration::from_micros(999)), "999.0µs");
+        assert_eq!(format_duration(Duration::from_micros(1_500)), "1.5ms");
+        assert_eq!(format_duration(Duration::from_millis(840)), "840.0ms");
+        assert_eq!(format_duration(Duration::from_millis(12_345)), "12.35s");
+    }
+
+    #[test]
+    fn day_from_bin_name() {
+        assert_eq!(day_from_bin("target/debug/d12"), Some(12));
+        assert_eq!(day_from_bin("d05"), Some(5));
+        assert_eq!(day_from_bin("target/release/d15.exe"), Some(15));
+        assert_eq!(day_from_bin("target/debug/deps/d12-0123abcd"), None);
+        assert_eq!(day_from_bin("d1"), None);
+        assert_eq!(day_from_bin("d123"), None);
+        assert_eq!(day_from_bin("x12"), None);
+        assert_eq!(day_from_bin(""), None);
+    }
+}
@@ -0,0 +1,932 @@
+use std::{borrow::Cow, cmp::Ordering, fmt, num::ParseIntError, slice, str::FromStr};
+
+use itertools::Itertools;
+
+/// Default limit of nested lists when parsing.
+pub const MAX_DEPTH: usize = 10_000;
+
+/// Integer or list of values, written as `[1,[2,[3]]]`. Equality is
+/// structural, so `1` and `[1]` are different values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(untagged)
+)]
+pub enum Value {
+    Number(i64),
+    List(List),
+}
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Number(value)
+    }
+}
+impl From<i32> for Value {
+    fn from(value: i32) -> Self {
+        Value::Number(value.into())
+    }
+}
+impl From<u32> for Value {
+    fn from(value: u32) -> Self {
+        Value::Number(value.into())
+    }
+}
+impl<U, const N: usize> From<[U; N]> for Value
+where
+    U: Into<Value>,
+{
+    fn from(value: [U; N]) -> Self {
+        Value::List(value.into_iter().map(U::into).collect())
+    }
+}
+impl FromStr for Value {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Value::parse(s, true, MAX_DEPTH)
+    }
+}
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Number(number) => write!(f, "{number}"),
+            Value::List(list) => write!(f, "[{}]", list.iter().format(",")),
+        }
+    }
+}
+impl Value {
+    /// Parses a whole line. With `whitespace`, spaces are allowed around
+    /// brackets, commas and numbers, although not between a minus sign and
+    /// its digits. Lists nested deeper than `max_depth` are an error.
+    pub fn parse(s: &str, whitespace: bool, max_depth: usize) -> Result<Value, ParseError> {
+        let s = s.as_bytes();
+        let mut at = skip_whitespace(s, 0, whitespace);
+        if at == s.len() {
+            return Err(ParseError::EmptyString);
+        }
+
+        // Lists still waiting for their `]`, innermost last.
+        let mut open: Vec<List> = vec![];
+        loop {
+            let mut value = match s.get(at) {
+                None => return Err(ParseError::EndWithinList(at)),
+                Some(b'[') => {
+                    if open.len() == max_depth {
+                        return Err(ParseError::TooDeep {
+                            depth: max_depth,
+                            column: at,
+                        });
+                    }
+
+                    at = skip_whitespace(s, at + 1, whitespace);
+                    if s.get(at).copied() != Some(b']') {
+                        open.push(List::new());
+                        continue;
+                    }
+
+                    at += 1;
+                    Value::List(List::new())
+                }
+                Some(_) => {
+                    let (end, number) = Value::parse_number(s, at)?;
+                    at = end;
+                    number
+                }
+            };
+
+            // Closes as many lists as the value just parsed ends.
+            loop {
+                let Some(list) = open.last_mut() else {
+                    let end = skip_whitespace(s, at, whitespace);
+                    if end < s.len() {
+                        return Err(ParseError::TrailingData(
+                            end,
+                            String::from_utf8_lossy(&s[end..]).into_owned(),
+                        ));
+                    }
+
+                    return Ok(value);
+                };
+
+                list.push(value);
+                at = skip_whitespace(s, at, whitespace);
+                let next = *s.get(at).ok_or(ParseError::EndWithinList(at))?;
+                match next {
+                    b']' => {
+                        at += 1;
+                        value = Value::List(open.pop().unwrap());
+                    }
+                    b',' => {
+                        let comma = at;
+                        at = skip_whitespace(s, at + 1, whitespace);
+                        if s.get(at).copied() == Some(b']') {
+                            return Err(ParseError::TrailingComma(comma));
+                        }
+                        break;
+                    }
+                    _ => return Err(ParseError::UnexpectedCharacter(at, next as char)),
+                }
+            }
+        }
+    }
+
+    /// Parses the number starting at byte `at` of `s`, returning where it
+    /// ends.
+    fn parse_number(s: &[u8], at: usize) -> Result<(usize, Value), ParseError> {
+        // A minus sign only counts right before the digits, anything else is
+        // left for the integer parsing to reject.
+        let digits = at + usize::from(s[at] == b'-');
+        let mut digit_end = digits;
+        while s
+            .get(digit_end)
+            .copied()
+            .unwrap_or_default()
+            .is_ascii_digit()
+        {
+            digit_end += 1;
+        }
+
+        if let (true, Some(&next)) = (digit_end == digits, s.get(digit_end)) {
+            return Err(ParseError::UnexpectedCharacter(digit_end, next as char));
+        }
+
+        let number = std::str::from_utf8(&s[at..digit_end])
+            .unwrap()
+            .parse()
+            .map_err(|e| ParseError::Number(at, e))?;
+
+        Ok((digit_end, Value::Number(number)))
+    }
+
+    /// The items of a list, or a number as a list of just itself.
+    pub fn items(&self) -> Iter<'_> {
+        match self {
+            Value::Number(number) => Iter(IterItems::Numbers(slice::from_ref(number).iter())),
+            Value::List(list) => list.iter(),
+        }
+    }
+
+    /// Order of day 13 of 2022: numbers compare as numbers, lists item by
+    /// item and then by length, and a number against a list is promoted to a
+    /// list of just that number. Doesn't allocate unless lists are nested
+    /// very deep.
+    pub fn cmp_promoting(&self, other: &Value) -> Ordering {
+        match (self, other) {
+            (Value::Number(a), Value::Number(b)) => a.cmp(b),
+            _ => cmp_lists(self.items(), other.items(), 0),
+        }
+    }
+}
+
+/// Items of a [`Value::List`]. Up to [`List::INLINE`] numbers, as most lists
+/// of day 13 are, are kept inline rather than on the heap. Lists holding
+/// lists can't be: a value would then contain values and have no finite
+/// size. Equality doesn't depend on where the items are kept.
+#[derive(Clone)]
+pub struct List(Items);
+#[derive(Clone)]
+enum Items {
+    Numbers {
+        len: u8,
+        numbers: [i64; List::INLINE],
+    },
+    Values(Vec<Value>),
+}
+impl List {
+    /// Most numbers kept inline.
+    pub const INLINE: usize = 4;
+
+    pub const fn new() -> List {
+        List(Items::Numbers {
+            len: 0,
+            numbers: [0; List::INLINE],
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.0 {
+            Items::Numbers { len, .. } => (*len).into(),
+            Items::Values(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether the items are kept inline, without an allocation of their own.
+    pub fn is_inline(&self) -> bool {
+        matches!(self.0, Items::Numbers { .. })
+    }
+
+    /// Adds `value` at the end, moving the items to the heap once they no
+    /// longer fit inline.
+    pub fn push(&mut self, value: Value) {
+        match (&mut self.0, value) {
+            (Items::Numbers { len, numbers }, Value::Number(number))
+                if usize::from(*len) < List::INLINE =>
+            {
+                numbers[usize::from(*len)] = number;
+                *len += 1;
+            }
+            (Items::Numbers { len, numbers }, value) => {
+                let mut values = Vec::with_capacity(2 * List::INLINE);
+                values.extend(
+                    numbers[..usize::from(*len)]
+                        .iter()
+                        .copied()
+                        .map(Value::Number),
+                );
+                values.push(value);
+                self.0 = Items::Values(values);
+            }
+            (Items::Values(values), value) => values.push(value),
+        }
+    }
+
+    /// The items in order, those kept inline as new values.
+    pub fn iter(&self) -> Iter<'_> {
+        match &self.0 {
+            Items::Numbers { len, numbers } => {
+                Iter(IterItems::Numbers(numbers[..usize::from(*len)].iter()))
+            }
+            Items::Values(values) => Iter(IterItems::Values(values.iter())),
+        }
+    }
+}
+impl Default for List {
+    fn default() -> Self {
+        List::new()
+    }
+}
+impl Drop for List {
+    /// Takes the lists within apart one at a time, as they may be nested
+    /// deeper than dropping them recursively would fit in the stack.
+    fn drop(&mut self) {
+        let Items::Values(values) = &mut self.0 else {
+            return;
+        };
+        let mut values = std::mem::take(values);
+        while let Some(value) = values.pop() {
+            if let Value::List(mut list) = value {
+                if let Items::Values(inner) = &mut list.0 {
+                    values.append(inner);
+                }
+            }
+        }
+    }
+}
+impl From<Vec<Value>> for List {
+    fn from(values: Vec<Value>) -> Self {
+        let numbers = values.iter().all(|value| matches!(value, Value::Number(_)));
+        match numbers && values.len() <= List::INLINE {
+            true => values.into_iter().collect(),
+            false => List(Items::Values(values)),
+        }
+    }
+}
+impl FromIterator<Value> for List {
+    fn from_iter<T: IntoIterator<Item = Value>>(iter: T) -> Self {
+        let mut list = List::new();
+        for value in iter {
+            list.push(value);
+        }
+        list
+    }
+}
+impl<'a> IntoIterator for &'a List {
+    type Item = Cow<'a, Value>;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+impl PartialEq for List {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other)
+    }
+}
+impl Eq for List {}
+impl fmt::Debug for List {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+#[cfg(feature = "serde")]
+impl serde::Serialize for List {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self)
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for List {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <Vec<Value> as serde::Deserialize>::deserialize(deserializer).map(List::from)
+    }
+}
+
+/// Items of a [`List`], from [`List::iter`] or [`Value::items`].
+#[derive(Clone)]
+pub struct Iter<'a>(IterItems<'a>);
+#[derive(Clone)]
+enum IterItems<'a> {
+    Numbers(slice::Iter<'a, i64>),
+    Values(slice::Iter<'a, Value>),
+    /// A number promoted to a list, which an iterator over inline numbers
+    /// can only give away.
+    Number(Option<i64>),
+}
+impl<'a> Iter<'a> {
+    /// The items of `value`, a number as a list of just itself.
+    fn of(value: Cow<'a, Value>) -> Iter<'a> {
+        match value {
+            Cow::Borrowed(value) => value.items(),
+            Cow::Owned(Value::Number(number)) => Iter(IterItems::Number(Some(number))),
+            Cow::Owned(Value::List(_)) => unreachable!("lists are only iterated by reference"),
+        }
+    }
+}
+impl<'a> Iterator for Iter<'a> {
+    type Item = Cow<'a, Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            IterItems::Numbers(numbers) => numbers.next().map(|&x| Cow::Owned(Value::Number(x))),
+            IterItems::Values(values) => values.next().map(Cow::Borrowed),
+            IterItems::Number(number) => number.take().map(|x| Cow::Owned(Value::Number(x))),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.0 {
+            IterItems::Numbers(numbers) => numbers.size_hint(),
+            IterItems::Values(values) => values.size_hint(),
+            IterItems::Number(number) => (number.iter().len(), Some(number.iter().len())),
+        }
+    }
+}
+impl ExactSizeIterator for Iter<'_> {}
+/// Deterministic pseudo random value at most `depth` lists deep, with at
+/// most `fanout` items per list. Shared by the tests of this module and of
+/// the day 13 binary.
+#[doc(hidden)]
+pub fn random_value(seed: &mut u64, depth: usize, fanout: u64) -> Value {
+    let mut next = |bound: u64| {
+        *seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (*seed >> 33) % bound
+    };
+
+    if depth == 0 || next(3) == 0 {
+        return Value::Number(next(20) as i64 - 5);
+    }
+
+    let len = next(fanout + 1);
+    Value::List(
+        (0..len)
+            .map(|_| random_value(seed, depth - 1, fanout))
+            .collect(),
+    )
+}
+#[cfg(feature = "serde")]
+impl TryFrom<serde_json::Value> for Value {
+    type Error = JsonError;
+
+    fn try_from(value: serde_json::Value) -> Result<Self, Self::Error> {
+        match value {
+            serde_json::Value::Number(number) => number
+                .as_i64()
+                .map(Value::Number)
+                .ok_or(JsonError::NotAnInteger(number)),
+            serde_json::Value::Array(items) => items
+                .into_iter()
+                .map(Value::try_from)
+                .collect::<Result<_, _>>()
+                .map(Value::List),
+            other => Err(JsonError::Unexpected(other)),
+        }
+    }
+}
+#[cfg(feature = "serde")]
+impl From<&Value> for serde_json::Value {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Number(number) => (*number).into(),
+            Value::List(list) => list.iter().map(|x| serde_json::Value::from(&*x)).collect(),
+        }
+    }
+}
+
+fn skip_whitespace(s: &[u8], at: usize, whitespace: bool) -> usize {
+    match whitespace {
+        true => {
+            at + s[at..]
+                .iter()
+                .take_while(|x| x.is_ascii_whitespace())
+                .count()
+        }
+        false => at,
+    }
+}
+
+/// Lists nested deeper than this are compared with a stack of their own
+/// rather than by recursion, which is cheaper but bounded by the thread stack.
+const RECURSION_LIMIT: usize = 64;
+
+fn cmp_lists(a: Iter, b: Iter, depth: usize) -> Ordering {
+    if depth == RECURSION_LIMIT {
+        return cmp_deep_lists(a, b);
+    }
+
+    let lengths = a.len().cmp(&b.len());
+    for (a, b) in a.zip(b) {
+        let ordering = match (&*a, &*b) {
+            (Value::Number(a), Value::Number(b)) => a.cmp(b),
+            (a, b) => cmp_lists(a.items(), b.items(), depth + 1),
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+
+    lengths
+}
+
+fn cmp_deep_lists(a: Iter, b: Iter) -> Ordering {
+    let mut stack = vec![(a, b)];
+
+    while let Some((a, b)) = stack.last_mut() {
+        match (a.next(), b.next()) {
+            (None, None) => {
+                stack.pop();
+            }
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (&*a, &*b) {
+                (Value::Number(a), Value::Number(b)) => {
+                    if a != b {
+                        return a.cmp(b);
+                    }
+                }
+                _ => stack.push((Iter::of(a), Iter::of(b))),
+            },
+        }
+    }
+
+    Ordering::Equal
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ParseError {
+    #[error("Empty string")]
+    EmptyString,
+    #[error("Trailing data after the value at column {}: {1:?}", .0 + 1)]
+    TrailingData(usize, String),
+    #[error("Line abruptly ends within a list at column {}", .0 + 1)]
+    EndWithinList(usize),
+    #[error("Trailing comma at column {}", .0 + 1)]
+    TrailingComma(usize),
+    #[error("More than {depth} nested lists at column {}", .column + 1)]
+    TooDeep {
+        depth: usize,
+        /// Byte offset of the `[` going too deep.
+        column: usize,
+    },
+    #[error("Unexpected character {1:?} at column {}", .0 + 1)]
+    UnexpectedCharacter(usize, char),
+    #[error("Invalid number at column {}: {1}", .0 + 1)]
+    Number(usize, ParseIntError),
+}
+impl ParseError {
+    /// Byte offset of the error within the line, if known.
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            ParseError::TrailingData(offset, _)
+            | ParseError::EndWithinList(offset)
+            | ParseError::TrailingComma(offset)
+            | ParseError::UnexpectedCharacter(offset, _)
+            | ParseError::Number(offset, _)
+            | ParseError::TooDeep { column: offset, .. } => Some(*offset),
+            ParseError::EmptyString => None,
+        }
+    }
+}
+impl From<ParseError> for crate::Error {
+    fn from(value: ParseError) -> Self {
+        crate::Error::Parsing(value.into())
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(thiserror::Error, Debug)]
+pub enum JsonError {
+    #[error("Numbers must be 64-bit integers, found {0}")]
+    NotAnInteger(serde_json::Number),
+    #[error("Values are made of numbers and arrays, found {0}")]
+    Unexpected(serde_json::Value),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        cell::Cell,
+    };
+
+    use super::*;
+
+    /// Counts the allocations of each thread, so that tests running in
+    /// parallel don't get in the way.
+    struct CountingAllocator;
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|x| x.set(x.get() + 1));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    fn allocations() -> usize {
+        ALLOCATIONS.with(Cell::get)
+    }
+
+    /// The pairs of the puzzle statement, with how they compare.
+    const PAIRS: [(&str, &str, Ordering); 8] = [
+        ("[1,1,3,1,1]", "[1,1,5,1,1]", Ordering::Less),
+        ("[[1],[2,3,4]]", "[[1],4]", Ordering::Less),
+        ("[9]", "[[8,7,6]]", Ordering::Greater),
+        ("[[4,4],4,4]", "[[4,4],4,4,4]", Ordering::Less),
+        ("[7,7,7,7]", "[7,7,7]", Ordering::Greater),
+        ("[]", "[3]", Ordering::Less),
+        ("[[[]]]", "[[]]", Ordering::Greater),
+        (
+            "[1,[2,[3,[4,[5,6,7]]]],8,9]",
+            "[1,[2,[3,[4,[5,6,0]]]],8,9]",
+            Ordering::Greater,
+        ),
+    ];
+
+    /// The comparison as first written, wrapping numbers in a new list.
+    fn allocating_cmp(a: &Value, b: &Value) -> Ordering {
+        match (a, b) {
+            (Value::Number(a), Value::Number(b)) => a.cmp(b),
+            (Value::Number(a), b @ Value::List(_)) => {
+                allocating_cmp(&Value::List(vec![Value::Number(*a)].into()), b)
+            }
+            (a @ Value::List(_), Value::Number(b)) => {
+                allocating_cmp(a, &Value::List(vec![Value::Number(*b)].into()))
+            }
+            (Value::List(a), Value::List(b)) => {
+                let mut a = a.iter();
+                let mut b = b.iter();
+
+                loop {
+                    match (a.next(), b.next()) {
+                        (None, None) => break Ordering::Equal,
+                        (None, Some(_)) => break Ordering::Less,
+                        (Some(_), None) => break Ordering::Greater,
+                        (Some(a), Some(b)) => match allocating_cmp(&a, &b) {
+                            Ordering::Equal => continue,
+                            other => break other,
+                        },
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn puzzle_pairs() {
+        for (left, right, ordering) in PAIRS {
+            let (left, right) = (left.parse::<Value>().unwrap(), right.parse().unwrap());
+
+            assert_eq!(left.cmp_promoting(&right), ordering, "{left} {right}");
+            assert_eq!(right.cmp_promoting(&left), ordering.reverse());
+            assert_eq!(left.cmp_promoting(&right), allocating_cmp(&left, &right));
+        }
+
+        assert_ne!(Value::from(1), Value::from([1]));
+        assert!(Value::from(1).cmp_promoting(&[1].into()).is_eq());
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Value::from(3).to_string(), "3");
+        assert_eq!(Value::List(List::new()).to_string(), "[]");
+        assert_eq!(Value::from([[1], [2]]).to_string(), "[[1],[2]]");
+        assert_eq!(
+            Value::List(vec![1.into(), [2.into(), Value::from([3])].into()].into()).to_string(),
+            "[1,[2,[3]]]"
+        );
+
+        for (left, right, _) in PAIRS {
+            for line in [left, right] {
+                let value: Value = line.parse().unwrap();
+                assert_eq!(value.to_string(), line);
+                assert_eq!(value.to_string().parse::<Value>().unwrap(), value);
+            }
+        }
+    }
+
+    #[test]
+    fn random_round_trip() {
+        let mut seed = 13;
+        for _ in 0..1000 {
+            let value = random_value(&mut seed, 5, 4);
+            let display = value.to_string();
+
+            assert_eq!(display.parse::<Value>().unwrap(), value, "{display}");
+        }
+    }
+
+    #[test]
+    fn signed() {
+        let value = |s: &str| s.parse::<Value>().unwrap();
+
+        assert_eq!(value("[-3,10000000000]"), [-3_i64, 10000000000].into());
+        assert_eq!(value("-0"), 0.into());
+        assert!(value("[-1]").cmp_promoting(&value("[0]")).is_lt());
+        assert!(value("[-10]").cmp_promoting(&value("[-9]")).is_lt());
+        assert!(value("[[-1],4]").cmp_promoting(&value("[0]")).is_lt());
+        assert!(value("[4294967296]")
+            .cmp_promoting(&value("[4294967295]"))
+            .is_gt());
+        assert_eq!(
+            value("[9223372036854775807]").to_string(),
+            "[9223372036854775807]"
+        );
+
+        for invalid in ["-", "[-]", "[1,-]", "[--1]", "[1-2]", "[- 1]", "[-[1]]"] {
+            assert!(invalid.parse::<Value>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn error_columns() {
+        let error = |s: &str| s.parse::<Value>().unwrap_err();
+
+        assert!(matches!(error("[1,]"), ParseError::TrailingComma(2)));
+        assert!(matches!(
+            error("[1 2]"),
+            ParseError::UnexpectedCharacter(3, '2')
+        ));
+        assert!(matches!(error("[[1]"), ParseError::EndWithinList(4)));
+        assert!(matches!(error("[[1]]]"), ParseError::TrailingData(5, _)));
+        assert!(matches!(
+            error("[1,[2,x]]"),
+            ParseError::UnexpectedCharacter(6, 'x')
+        ));
+        assert!(matches!(
+            error("[1,99999999999999999999]"),
+            ParseError::Number(3, _)
+        ));
+        assert_eq!(
+            error("[1 2]").to_string(),
+            "Unexpected character '2' at column 4"
+        );
+        assert_eq!(error("[1 2]").offset(), Some(3));
+        assert_eq!(error("").offset(), None);
+    }
+
+    #[test]
+    fn whitespace() {
+        let strict = |s: &str| Value::parse(s, false, MAX_DEPTH);
+
+        assert_eq!("[1, 2]".parse::<Value>().unwrap(), [1, 2].into());
+        assert_eq!(
+            " [ [ ] , -1 ,[2 ]] ".parse::<Value>().unwrap(),
+            Value::List(vec![Value::List(List::new()), (-1).into(), [2].into()].into())
+        );
+        assert!(matches!(
+            strict("[1, 2]"),
+            Err(ParseError::UnexpectedCharacter(3, ' '))
+        ));
+        assert!(matches!(
+            strict("[1 ]"),
+            Err(ParseError::UnexpectedCharacter(2, ' '))
+        ));
+        assert!(matches!(
+            "  ".parse::<Value>(),
+            Err(ParseError::EmptyString)
+        ));
+
+        // Trailing commas are never allowed, and a leading one is where an
+        // element should be, at offset 1.
+        assert!(matches!(
+            "[1,]".parse::<Value>(),
+            Err(ParseError::TrailingComma(2))
+        ));
+        assert!(matches!(
+            "[1 , ]".parse::<Value>(),
+            Err(ParseError::TrailingComma(3))
+        ));
+        assert!(matches!(strict("[[],]"), Err(ParseError::TrailingComma(3))));
+        assert!(matches!(
+            "[,1]".parse::<Value>(),
+            Err(ParseError::UnexpectedCharacter(1, ','))
+        ));
+
+        for (left, right, _) in PAIRS {
+            for line in [left, right] {
+                assert_eq!(strict(line).unwrap(), line.parse().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn cmp_oracle() {
+        let mut seed = 1970;
+        for _ in 0..5000 {
+            let a = random_value(&mut seed, 6, 3);
+            let b = random_value(&mut seed, 6, 3);
+
+            assert_eq!(a.cmp_promoting(&b), allocating_cmp(&a, &b), "{a} {b}");
+            assert_eq!(b.cmp_promoting(&a), allocating_cmp(&b, &a), "{b} {a}");
+        }
+    }
+
+    #[test]
+    fn cmp_allocations() {
+        let mut seed = 19700;
+        let mut values = (0..5000)
+            .map(|_| random_value(&mut seed, 6, 3))
+            .collect::<Vec<_>>();
+        let mut oracle = values.clone();
+
+        let before = allocations();
+        values.sort_unstable_by(Value::cmp_promoting);
+        assert_eq!(allocations(), before);
+
+        oracle.sort_unstable_by(allocating_cmp);
+        assert!(allocations() > before);
+        assert!(values
+            .iter()
+            .zip(&oracle)
+            .all(|(a, b)| a.cmp_promoting(b).is_eq()));
+    }
+
+    #[test]
+    fn inline_boundary() {
+        for len in [3, 4, 5] {
+            let inline = len <= List::INLINE as i64;
+            let numbers = (1..=len).map(Value::Number).collect::<List>();
+            assert_eq!(numbers.len(), len as usize);
+            assert_eq!(numbers.is_inline(), inline);
+
+            let value = Value::List(numbers);
+            let line = value.to_string();
+            assert_eq!(line, format!("[{}]", (1..=len).join(",")));
+            let Value::List(parsed) = line.parse().unwrap() else {
+                panic!("{line} is not a list");
+            };
+            assert_eq!(parsed.is_inline(), inline);
+            assert_eq!(Value::List(parsed), value);
+
+            let longer = format!("[{},9]", (1..=len).join(",")).parse().unwrap();
+            assert!(value.cmp_promoting(&longer).is_lt());
+            assert_eq!(
+                value.cmp_promoting(&longer),
+                allocating_cmp(&value, &longer)
+            );
+
+            // A list among them always takes them to the heap.
+            let mut nested = (1..len).map(Value::Number).collect::<List>();
+            nested.push([len].into());
+            assert!(!nested.is_inline());
+            let nested = Value::List(nested);
+            assert!(value.cmp_promoting(&nested).is_eq());
+            assert_ne!(value, nested);
+        }
+    }
+
+    #[test]
+    fn representations() {
+        for len in 0..=List::INLINE as i64 {
+            let inline = (0..len).map(Value::Number).collect::<List>();
+            let heap = List(Items::Values((0..len).map(Value::Number).collect()));
+            assert!(inline.is_inline());
+            assert!(!heap.is_inline());
+
+            assert_eq!(inline, heap);
+            assert_eq!(heap, inline);
+            assert_eq!(format!("{inline:?}"), format!("{heap:?}"));
+            let (inline, heap) = (Value::List(inline), Value::List(heap));
+            assert_eq!(inline.to_string(), heap.to_string());
+            assert!(inline.cmp_promoting(&heap).is_eq());
+        }
+
+        let heap = List(Items::Values(vec![1.into(), 2.into()]));
+        assert_ne!(heap, [1, 3].into_iter().map(Value::from).collect());
+        assert_ne!(heap, [1].into_iter().map(Value::from).collect());
+
+        assert!(List::from(vec![Value::from(1); List::INLINE]).is_inline());
+        assert!(!List::from(vec![Value::from(1); List::INLINE + 1]).is_inline());
+        assert!(!List::from(vec![Value::from([1])]).is_inline());
+    }
+
+    /// Allocations while parsing many small values: one for the lists still
+    /// open in each line, and one per list on the heap, fewer than a vector
+    /// for each non-empty list would take.
+    #[test]
+    #[ignore]
+    fn parse_allocations() {
+        /// Non-empty lists, and those on the heap.
+        fn lists(value: &Value) -> (usize, usize) {
+            let Value::List(list) = value else {
+                return (0, 0);
+            };
+            list.iter().map(|x| lists(&x)).fold(
+                (
+                    usize::from(!list.is_empty()),
+                    usize::from(!list.is_inline()),
+                ),
+                |(a, b), (c, d)| (a + c, b + d),
+            )
+        }
+
+        let mut seed = 1973;
+        let lines = (0..50_000)
+            .map(|_| random_value(&mut seed, 4, 4).to_string())
+            .collect::<Vec<_>>();
+
+        let before = allocations();
+        let values = lines
+            .iter()
+            .map(|x| x.parse::<Value>().unwrap())
+            .collect::<Vec<_>>();
+        let allocated = allocations() - before;
+        let (lists, heap) = values
+            .iter()
+            .map(lists)
+            .fold((0, 0), |(a, b), (c, d)| (a + c, b + d));
+
+        eprintln!("{allocated} allocations for {lists} non-empty lists, {heap} on the heap");
+        assert!(allocated <= lines.len() + heap + 1);
+        assert!(allocated < lists);
+    }
+
+    #[test]
+    fn deep() {
+        let depth = 10_000;
+        let line = "[".repeat(depth) + "1" + &"]".repeat(depth);
+        let value = line.parse::<Value>().unwrap();
+        assert_eq!(value.cmp_promoting(&value), Ordering::Equal);
+
+        let shallower = line[1..line.len() - 1].parse::<Value>().unwrap();
+        assert_eq!(value.cmp_promoting(&shallower), Ordering::Equal);
+        assert_eq!(value.cmp_promoting(&Value::from(2)), Ordering::Less);
+
+        let line = "[".repeat(1_000_001);
+        assert!(matches!(
+            line.parse::<Value>(),
+            Err(ParseError::TooDeep {
+                depth: MAX_DEPTH,
+                column: MAX_DEPTH
+            })
+        ));
+        assert!(matches!(
+            Value::parse("[[[1]]]", false, 2),
+            Err(ParseError::TooDeep {
+                depth: 2,
+                column: 2
+            })
+        ));
+        assert!(Value::parse("[[1]]", false, 2).is_ok());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_value() {
+        let mut seed = 1966;
+        for _ in 0..1000 {
+            let value = random_value(&mut seed, 5, 4);
+            let json = serde_json::Value::from(&value);
+
+            assert_eq!(json.to_string(), value.to_string());
+            assert_eq!(serde_json::to_value(&value).unwrap(), json);
+            assert_eq!(Value::try_from(json.clone()).unwrap(), value);
+            assert_eq!(serde_json::from_value::<Value>(json).unwrap(), value);
+        }
+
+        let float = serde_json::json!([1, [2.5]]);
+        assert!(matches!(
+            Value::try_from(float),
+            Err(JsonError::NotAnInteger(_))
+        ));
+        assert!(matches!(
+            Value::try_from(serde_json::json!({ "a": 1 })),
+            Err(JsonError::Unexpected(_))
+        ));
+    }
+}
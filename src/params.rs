@@ -0,0 +1,390 @@
+use crate::{format_duration, Part};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Instant,
+};
+
+/// Day specific switches given on the command line, either as `--name`,
+/// `--name value` or `--param name=value`. `-e` reads the example and `-e2`,
+/// `-e3`... its other variants. `-v` turns verbose output on, `-i path`
+/// reads the input from `path` and `-1` or `-2`, or `--part 1` or
+/// `--part 2`, solve only that part of the puzzle. `-t` times the answer and
+/// its parts, `-b runs` or `--bench runs` benchmarks it over that many runs.
+/// `-c` or `--check` compares the answer with the one recorded in the
+/// answers file. `--json` prints the answer as JSON. `-h` or `--help` asks
+/// for [`USAGE`]. Underscores in names read as dashes, so `--param
+/// max_grains=10` is `--max-grains 10`.
+#[derive(Debug, Default, Clone)]
+pub struct Params {
+    help: bool,
+    example: bool,
+    variant: Option<u32>,
+    verbose: bool,
+    input: Option<PathBuf>,
+    part1: bool,
+    part2: bool,
+    timed: bool,
+    check: bool,
+    bench: Option<String>,
+    values: HashMap<String, Option<String>>,
+}
+impl Params {
+    /// Like [`Params::try_from_args`], panicking on a bad switch.
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Params {
+        Params::try_from_args(args).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Params, SwitchError> {
+        let mut params = Params::default();
+        let mut args = args.into_iter().peekable();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => params.help = true,
+                "-e" => params.example = true,
+                "-v" => params.verbose = true,
+                "-i" => params.input = Some(args.next().unwrap_or_default().into()),
+                "-1" => params.part1 = true,
+                "-2" => params.part2 = true,
+                "--part" => match args.next().as_deref() {
+                    Some("1") => params.part1 = true,
+                    Some("2") => params.part2 = true,
+                    other => return Err(SwitchError::Part(other.map(str::to_string))),
+                },
+                "-t" => params.timed = true,
+                "-c" => params.check = true,
+                "-b" | "--bench" => params.bench = Some(args.next().unwrap_or_default()),
+                "--param" => {
+                    let param = args.next().unwrap_or_default();
+                    let (name, value) = match param.split_once('=') {
+                        Some((name, value)) => (name, Some(value.to_string())),
+                        None => (param.as_str(), None),
+                    };
+                    params.values.insert(param_name(name), value);
+                }
+                x if example_variant(x).is_some() => {
+                    params.example = true;
+                    params.variant = example_variant(x);
+                }
+                x => match x.strip_prefix("--") {
+                    Some(name) => {
+                        let value = args.next_if(|value| !value.starts_with('-'));
+                        params.values.insert(param_name(name), value);
+                    }
+                    None => return Err(SwitchError::Unknown(x.to_string())),
+                },
+            }
+        }
+
+        Ok(params)
+    }
+
+    /// Whether `-h` or `--help` asked for [`USAGE`].
+    pub fn help(&self) -> bool {
+        self.help
+    }
+
+    pub fn example(&self) -> bool {
+        self.example
+    }
+
+    /// Variant of the input picked with `-e2`, `-e3`..., 1 otherwise.
+    pub fn variant(&self) -> u32 {
+        self.variant.unwrap_or(1)
+    }
+
+    pub fn verbose(&self) -> bool {
+        self.verbose
+    }
+
+    /// Input file given with `-i`, which takes precedence over `-e`.
+    pub fn input(&self) -> Option<&Path> {
+        self.input.as_deref()
+    }
+
+    /// Parts selected with `-1` and `-2`, both if none is, timed with `-t`.
+    pub fn parts(&self) -> Parts {
+        let (part1, part2) = match (self.part1, self.part2) {
+            (false, false) => (true, true),
+            selected => selected,
+        };
+        Parts {
+            part1,
+            part2,
+            timed: self.timed,
+        }
+    }
+
+    pub fn timed(&self) -> bool {
+        self.timed
+    }
+
+    pub fn check(&self) -> bool {
+        self.check || self.flag("check")
+    }
+
+    /// Number of runs to benchmark, given with `-b` or `--bench`.
+    pub fn bench(&self) -> Result<Option<u32>, ParamError> {
+        let Some(value) = &self.bench else {
+            return Ok(None);
+        };
+        let error = |reason: String| ParamError {
+            name: "-b",
+            value: value.clone(),
+            reason,
+        };
+        match value.parse::<u32>() {
+            Ok(0) => Err(error("at least one run needed".to_string())),
+            Ok(runs) => Ok(Some(runs)),
+            Err(e) => Err(error(e.to_string())),
+        }
+    }
+
+    pub fn with<S: Into<String>>(mut self, name: S, value: Option<&str>) -> Self {
+        self.values
+            .insert(param_name(&name.into()), value.map(str::to_string));
+        self
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name)?.as_deref()
+    }
+
+    pub fn parse<T>(&self, name: &'static str) -> Result<Option<T>, ParamError>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.get(name)
+            .map(|value| {
+                value.parse().map_err(|e: T::Err| ParamError {
+                    name,
+                    value: value.to_string(),
+                    reason: e.to_string(),
+                })
+            })
+            .transpose()
+    }
+}
+
+/// Name of a day specific switch, with its underscores as dashes.
+fn param_name(name: &str) -> String {
+    name.replace('_', "-")
+}
+
+/// Variant `n` of the example from a `-en` switch.
+fn example_variant(arg: &str) -> Option<u32> {
+    arg.strip_prefix("-e")?.parse().ok().filter(|&n| n > 0)
+}
+
+/// Parts of the puzzle to solve, and whether to print how long they take.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parts {
+    pub part1: bool,
+    pub part2: bool,
+    pub timed: bool,
+}
+impl Default for Parts {
+    fn default() -> Self {
+        Parts {
+            part1: true,
+            part2: true,
+            timed: false,
+        }
+    }
+}
+impl Parts {
+    /// Solves part 1 with `solve`, if selected.
+    pub fn part1<T, E, F: FnOnce() -> Result<T, E>>(self, solve: F) -> Result<Part<T>, E> {
+        self.solve(1, self.part1, solve)
+    }
+
+    /// Solves part 2 with `solve`, if selected.
+    pub fn part2<T, E, F: FnOnce() -> Result<T, E>>(self, solve: F) -> Result<Part<T>, E> {
+        self.solve(2, self.part2, solve)
+    }
+
+    fn solve<T, E, F>(self, number: u32, selected: bool, solve: F) -> Result<Part<T>, E>
+    where
+        F: FnOnce() -> Result<T, E>,
+    {
+        let begin = Instant::now();
+        let part = Part::solve(selected, solve);
+        if self.timed && selected {
+            eprintln!("Part {number}: {}", format_duration(begin.elapsed()));
+        }
+        part
+    }
+}
+
+/// The switches every day takes, printed for `-h` or `--help`.
+pub const USAGE: &str = "\
+Usage: dNN [switches] [--name [value]]...
+
+  -e, -eN               read the example, or its variant N
+  -i path               read the input from path
+  -v                    print what the day has to say along the way
+  -1, -2, --part N      solve only that part
+  -t                    time the answer and its parts
+  -b, --bench runs      benchmark over that many runs
+  -c, --check           compare with the answers file
+  --json                print the answer as JSON
+  --fetch               download the full input if missing
+  --name [value]        a switch of the day, also --param name=value
+  -h, --help            print this help";
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum SwitchError {
+    #[error("{0:?} is not a recognized CLI switch, see --help")]
+    Unknown(String),
+    #[error("--part expects 1 or 2, not {0:?}")]
+    Part(Option<String>),
+}
+impl From<SwitchError> for crate::Error {
+    fn from(value: SwitchError) -> Self {
+        crate::Error::Parsing(value.into())
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Invalid value {value:?} for {name:?}: {reason}")]
+pub struct ParamError {
+    pub name: &'static str,
+    pub value: String,
+    pub reason: String,
+}
+impl From<ParamError> for crate::Error {
+    fn from(value: ParamError) -> Self {
+        crate::Error::Parsing(value.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn input_path() {
+        let args = ["-e", "-i", "/tmp/input.txt", "--row", "3"].map(String::from);
+        let params = Params::from_args(args);
+        assert_eq!(params.input(), Some(Path::new("/tmp/input.txt")));
+        assert!(params.example());
+        assert_eq!(params.get("row"), Some("3"));
+
+        assert_eq!(Params::from_args(["-e".to_string()]).input(), None);
+    }
+
+    #[test]
+    fn underscores() {
+        let args = ["--param", "max_grains=10", "--full_sort"].map(String::from);
+        let params = Params::from_args(args);
+        assert_eq!(params.get("max-grains"), Some("10"));
+        assert!(params.flag("full-sort"));
+
+        let params = Params::default().with("dump_cave", Some("cave.pgm"));
+        assert_eq!(params.get("dump-cave"), Some("cave.pgm"));
+        assert_eq!(params.get("dump_cave"), None);
+    }
+
+    #[test]
+    fn example_variants() {
+        let params = |args: &[&str]| Params::from_args(args.iter().map(|x| x.to_string()));
+
+        assert!(!params(&[]).example());
+        assert_eq!(params(&[]).variant(), 1);
+        assert_eq!(params(&["-e"]).variant(), 1);
+        let second = params(&["-v", "-e2"]);
+        assert!(second.example());
+        assert_eq!(second.variant(), 2);
+        assert_eq!(params(&["-e12"]).variant(), 12);
+    }
+
+    #[test]
+    #[should_panic(expected = "\"-e0\" is not a recognized CLI switch")]
+    fn example_variant_zero() {
+        Params::from_args(["-e0".to_string()]);
+    }
+
+    #[test]
+    fn switch_errors() {
+        let params = |args: &[&str]| Params::try_from_args(args.iter().map(|x| x.to_string()));
+
+        assert!(!params(&["-e"]).unwrap().help());
+        assert!(params(&["-h"]).unwrap().help());
+        assert!(params(&["-e", "--help"]).unwrap().help());
+        assert!(!params(&["--help"]).unwrap().flag("help"));
+
+        assert_eq!(
+            params(&["-e", "-x"]).unwrap_err(),
+            SwitchError::Unknown("-x".to_string())
+        );
+        assert_eq!(
+            params(&["input.txt"]).unwrap_err().to_string(),
+            "\"input.txt\" is not a recognized CLI switch, see --help"
+        );
+        assert_eq!(params(&["--part"]).unwrap_err(), SwitchError::Part(None));
+        assert!(params(&["--part", "1", "-t"]).is_ok());
+    }
+
+    #[test]
+    fn bench_runs() {
+        let bench = |args: &[&str]| Params::from_args(args.iter().map(|x| x.to_string())).bench();
+
+        assert_eq!(bench(&["-e"]).unwrap(), None);
+        assert_eq!(bench(&["-b", "10", "-e"]).unwrap(), Some(10));
+        assert_eq!(
+            bench(&["-b", "0"]).unwrap_err().to_string(),
+            "Invalid value \"0\" for \"-b\": at least one run needed"
+        );
+        assert!(bench(&["-b", "x"]).is_err());
+        assert!(bench(&["-b"]).is_err());
+        assert_eq!(bench(&["--bench", "3"]).unwrap(), Some(3));
+        assert!(bench(&["--bench"]).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "--part expects 1 or 2, not Some(\"3\")")]
+    fn part_three() {
+        Params::from_args(["--part", "3"].map(String::from));
+    }
+
+    #[test]
+    fn parts() {
+        let parts = |args: &[&str]| Params::from_args(args.iter().map(|x| x.to_string())).parts();
+        let both = Parts::default();
+
+        assert_eq!(parts(&[]), both);
+        assert_eq!(parts(&["-1", "-2"]), both);
+        assert_eq!(
+            parts(&["-e", "-1"]),
+            Parts {
+                part2: false,
+                ..Parts::default()
+            }
+        );
+        assert_eq!(
+            parts(&["-2"]),
+            Parts {
+                part1: false,
+                ..Parts::default()
+            }
+        );
+
+        assert_eq!(parts(&["--part", "1"]), parts(&["-1"]));
+        assert_eq!(parts(&["--part", "2", "-e"]), parts(&["-2"]));
+        assert!(parts(&["-t", "-1"]).timed);
+        assert!(!parts(&["-1"]).timed);
+
+        let solved = parts(&["-2"]).part2(|| Ok::<_, ()>(3));
+        assert_eq!(solved, Ok(Part::Solved(3)));
+        let skipped = parts(&["-2"]).part1(|| -> Result<u32, ()> { panic!("not selected") });
+        assert_eq!(skipped, Ok(Part::Skipped));
+        assert_eq!(both.part1(|| Err::<u32, _>("failed")), Err("failed"));
+    }
+}
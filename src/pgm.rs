@@ -0,0 +1,74 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::grid::Grid;
+
+/// Writes `image` as a binary (P5) grayscale PGM, one byte per pixel.
+pub fn write<W: Write>(mut w: W, image: &Grid<u8>) -> io::Result<()> {
+    write!(w, "P5\n{} {}\n255\n", image.width(), image.height())?;
+    for y in 0..image.height() {
+        w.write_all(image.line(y))?;
+    }
+
+    Ok(())
+}
+
+pub fn save<P: AsRef<Path>>(path: P, image: &Grid<u8>) -> Result<(), PgmError> {
+    save_with(path, |file| write(file, image))
+}
+
+/// Creates `path` and fills it with `write`, for the other formats an image
+/// may be dumped as.
+pub fn save_with<P, F>(path: P, write: F) -> Result<(), PgmError>
+where
+    P: AsRef<Path>,
+    F: FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+{
+    let path = path.as_ref();
+    let error = |e| PgmError(path.to_path_buf(), e);
+
+    let mut file = BufWriter::new(File::create(path).map_err(error)?);
+    write(&mut file).map_err(error)?;
+    file.flush().map_err(error)
+}
+
+/// Scales `value` out of `max` to a shade between `darkest` and white.
+pub fn shade(value: usize, max: usize, darkest: u8) -> u8 {
+    let range = usize::from(u8::MAX - darkest);
+    (usize::from(darkest) + value.min(max) * range / max.max(1)) as u8
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Error writing image {0}: {1}")]
+pub struct PgmError(PathBuf, io::Error);
+impl From<PgmError> for crate::Error {
+    fn from(value: PgmError) -> Self {
+        crate::Error::Semantic(value.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header() {
+        let mut buffer = vec![];
+        write(&mut buffer, &Grid::new(3, vec![0, 1, 2, 3, 4, 5])).unwrap();
+
+        assert_eq!(buffer, b"P5\n3 2\n255\n\x00\x01\x02\x03\x04\x05");
+    }
+
+    #[test]
+    fn shades() {
+        assert_eq!(shade(0, 25, 0), 0);
+        assert_eq!(shade(25, 25, 0), 255);
+        assert_eq!(shade(0, 31, 1), 1);
+        assert_eq!(shade(31, 31, 1), 255);
+        assert_eq!(shade(40, 31, 1), 255);
+        assert_eq!(shade(0, 0, 0), 0);
+    }
+}
@@ -0,0 +1,401 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+    time::{Duration, Instant},
+};
+
+use crate::grid::{Coord, Grid};
+
+/// Exact counters of what a search did.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SearchStats {
+    /// Cells taken out of the queue and looked at.
+    pub expanded: usize,
+    /// Distance updates, including the sources.
+    pub relaxed: usize,
+    /// Distance updates of cells that already had a distance.
+    pub re_relaxed: usize,
+    pub max_queue: usize,
+    pub elapsed: Duration,
+}
+impl SearchStats {
+    fn queued(&mut self, len: usize) {
+        self.max_queue = self.max_queue.max(len);
+    }
+}
+impl std::fmt::Display for SearchStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "expanded {} cells, relaxed {} ({} more than once), max queue {}, took {:?}",
+            self.expanded, self.relaxed, self.re_relaxed, self.max_queue, self.elapsed
+        )
+    }
+}
+
+/// Distances found by a search, along with the way back to the sources.
+#[derive(Clone, Debug)]
+pub struct DistanceMap {
+    distances: Grid<Option<usize>>,
+    previous: Grid<Option<Coord>>,
+}
+impl DistanceMap {
+    fn new<T>(grid: &Grid<T>) -> Self {
+        DistanceMap {
+            distances: Grid::filled(grid.width(), grid.height(), None),
+            previous: Grid::filled(grid.width(), grid.height(), None),
+        }
+    }
+
+    /// Steps from the closest source, `None` when unreached or outside.
+    pub fn distance(&self, coord: Coord) -> Option<usize> {
+        self.distances.get(coord).copied().flatten()
+    }
+
+    pub fn distances(&self) -> &Grid<Option<usize>> {
+        &self.distances
+    }
+
+    /// The cells of a shortest path from the closest source up to `goal`,
+    /// both included.
+    pub fn path_to(&self, goal: Coord) -> Option<Vec<Coord>> {
+        let distance = self.distance(goal)?;
+        let mut path = vec![goal];
+        while let Some(previous) = self.previous[*path.last().unwrap()] {
+            path.push(previous);
+        }
+        path.reverse();
+
+        debug_assert_eq!(path.len() - 1, distance);
+        Some(path)
+    }
+
+    /// Records that `to` can be reached in `distance` steps coming `from` the
+    /// given cell, if that is shorter than what was known.
+    fn relax(
+        &mut self,
+        from: Option<Coord>,
+        to: Coord,
+        distance: usize,
+        stats: &mut SearchStats,
+    ) -> bool {
+        let known = &mut self.distances[to];
+        let re_relaxed = match known {
+            Some(known) if *known <= distance => return false,
+            Some(_) => true,
+            None => false,
+        };
+
+        *known = Some(distance);
+        self.previous[to] = from;
+        stats.relaxed += 1;
+        if re_relaxed {
+            stats.re_relaxed += 1;
+        }
+
+        true
+    }
+}
+
+/// Breadth-first search on `grid` from all of `sources` at once, where
+/// `neighbors` yields the cells one step away from a cell. Every step costs
+/// the same, so each cell is settled the first time it is visited.
+pub fn bfs<T, S, N, I>(grid: &Grid<T>, sources: S, mut neighbors: N) -> (DistanceMap, SearchStats)
+where
+    S: IntoIterator<Item = Coord>,
+    N: FnMut(Coord) -> I,
+    I: IntoIterator<Item = Coord>,
+{
+    let begin = Instant::now();
+    let mut stats = SearchStats::default();
+    let mut map = DistanceMap::new(grid);
+    let mut queue = VecDeque::new();
+    for source in sources {
+        if map.relax(None, source, 0, &mut stats) {
+            queue.push_back(source);
+        }
+    }
+    stats.queued(queue.len());
+
+    while let Some(current) = queue.pop_front() {
+        stats.expanded += 1;
+        let distance = map.distance(current).unwrap_or_default() + 1;
+
+        for next in neighbors(current) {
+            if map.relax(Some(current), next, distance, &mut stats) {
+                queue.push_back(next);
+            }
+        }
+        stats.queued(queue.len());
+    }
+
+    stats.elapsed = begin.elapsed();
+    (map, stats)
+}
+
+/// A* search on `grid` from `sources` that stops as soon as a cell passing
+/// `is_goal` is settled. `estimate` must never be above the real number of
+/// steps left to the closest goal. Only the distances of expanded cells are
+/// final.
+pub fn astar<T, S, N, I, E, G>(
+    grid: &Grid<T>,
+    sources: S,
+    mut neighbors: N,
+    mut estimate: E,
+    mut is_goal: G,
+) -> (DistanceMap, SearchStats)
+where
+    S: IntoIterator<Item = Coord>,
+    N: FnMut(Coord) -> I,
+    I: IntoIterator<Item = Coord>,
+    E: FnMut(Coord) -> usize,
+    G: FnMut(Coord) -> bool,
+{
+    let begin = Instant::now();
+    let mut stats = SearchStats::default();
+    let mut map = DistanceMap::new(grid);
+    let mut heap = BinaryHeap::new();
+    for source in sources {
+        if map.relax(None, source, 0, &mut stats) {
+            heap.push((Reverse(estimate(source)), 0, source));
+        }
+    }
+    stats.queued(heap.len());
+
+    // Equal estimates favour the deepest cell, which goes straight to the goal
+    // on open maps.
+    while let Some((_, distance, current)) = heap.pop() {
+        if map.distance(current) != Some(distance) {
+            continue;
+        }
+
+        stats.expanded += 1;
+        if is_goal(current) {
+            break;
+        }
+
+        let distance = distance + 1;
+        for next in neighbors(current) {
+            if map.relax(Some(current), next, distance, &mut stats) {
+                heap.push((Reverse(distance + estimate(next)), distance, next));
+            }
+        }
+        stats.queued(heap.len());
+    }
+
+    stats.elapsed = begin.elapsed();
+    (map, stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::Adjacency;
+
+    use super::*;
+
+    /// Height map in the letters format of day 12, with its start and end.
+    fn heights<S: AsRef<str>>(lines: &[S]) -> (Grid<u8>, Coord, Coord) {
+        let (mut start, mut end) = ((0, 0), (0, 0));
+        let mut cells = vec![];
+        for (y, line) in lines.iter().enumerate() {
+            for (x, char) in line.as_ref().bytes().enumerate() {
+                cells.push(match char {
+                    b'S' => {
+                        start = (x, y);
+                        0
+                    }
+                    b'E' => {
+                        end = (x, y);
+                        25
+                    }
+                    char => char - b'a',
+                });
+            }
+        }
+
+        let width = lines.first().map_or(0, |x| x.as_ref().len());
+        (Grid::new(width, cells), start, end)
+    }
+
+    fn example() -> (Grid<u8>, Coord, Coord) {
+        heights(
+            &crate::input(12, true)
+                .map(Result::unwrap)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Whether a step from `from` to `to` climbs at most one level, dropping
+    /// any.
+    fn steps_up(grid: &Grid<u8>, from: Coord, to: Coord) -> bool {
+        grid[to] <= grid[from] + 1
+    }
+
+    /// The neighbors one allowed step away.
+    fn climb(grid: &Grid<u8>) -> impl Fn(Coord) -> Vec<Coord> + '_ {
+        move |from| {
+            grid.neighbors(from, Adjacency::Orthogonal)
+                .filter(|&to| steps_up(grid, from, to))
+                .collect()
+        }
+    }
+
+    /// The same steps walked backwards, from the end towards the start.
+    fn descend(grid: &Grid<u8>) -> impl Fn(Coord) -> Vec<Coord> + '_ {
+        move |from| {
+            grid.neighbors(from, Adjacency::Orthogonal)
+                .filter(|&to| steps_up(grid, to, from))
+                .collect()
+        }
+    }
+
+    /// Reference single source shortest paths, to compare against the BFS.
+    fn dijkstra(
+        grid: &Grid<u8>,
+        start: Coord,
+        neighbors: impl Fn(Coord) -> Vec<Coord>,
+    ) -> Grid<Option<usize>> {
+        let mut distances = grid.map(|_| None);
+        let mut heap = BinaryHeap::from([(Reverse(0), start)]);
+
+        while let Some((Reverse(distance), current)) = heap.pop() {
+            if distances[current].is_some() {
+                continue;
+            }
+            distances[current] = Some(distance);
+
+            for next in neighbors(current) {
+                heap.push((Reverse(distance + 1), next));
+            }
+        }
+
+        distances
+    }
+
+    #[test]
+    fn example_climbs() {
+        let (grid, start, end) = example();
+
+        let (forward, _) = bfs(&grid, [start], climb(&grid));
+        assert_eq!(forward.distance(end), Some(31));
+        assert_eq!(forward.path_to(end).map(|x| x.len()), Some(32));
+
+        let (reverse, _) = bfs(&grid, [end], descend(&grid));
+        assert_eq!(reverse.distance(start), Some(31));
+        let lowest = grid
+            .coords()
+            .filter(|&x| grid[x] == 0)
+            .filter_map(|x| reverse.distance(x))
+            .min();
+        assert_eq!(lowest, Some(29));
+
+        let (astar, _) = astar(
+            &grid,
+            [start],
+            climb(&grid),
+            |(x, y)| x.abs_diff(end.0) + y.abs_diff(end.1),
+            |x| x == end,
+        );
+        assert_eq!(astar.distance(end), Some(31));
+    }
+
+    #[test]
+    fn stats() {
+        let (grid, start, _) = example();
+        let (distances, stats) = bfs(&grid, [start], climb(&grid));
+        let reachable = distances.distances().cells().flatten().count();
+
+        assert_eq!(stats.expanded, reachable);
+        assert_eq!(stats.relaxed, reachable);
+        assert_eq!(stats.re_relaxed, 0);
+        assert!(stats.max_queue > 0 && stats.max_queue <= grid.cells().count());
+    }
+
+    #[test]
+    fn stats_re_relaxed() {
+        // Depth first order relaxes cells again when a shorter way shows up,
+        // which is what the counter is there to catch.
+        let (grid, start, end) = example();
+        let neighbors = climb(&grid);
+        let mut map = DistanceMap::new(&grid);
+        let mut stats = SearchStats::default();
+        let mut stack = vec![start];
+        map.relax(None, start, 0, &mut stats);
+
+        while let Some(current) = stack.pop() {
+            stats.expanded += 1;
+            let distance = map.distance(current).unwrap() + 1;
+            for next in neighbors(current) {
+                if map.relax(Some(current), next, distance, &mut stats) {
+                    stack.push(next);
+                }
+            }
+        }
+
+        assert!(stats.re_relaxed > 0);
+        assert_eq!(stats.relaxed, 40 + stats.re_relaxed);
+        assert_eq!(map.distance(end), Some(31));
+    }
+
+    #[test]
+    fn astar_expansions() {
+        let grid = Grid::filled(100, 100, 0);
+        let goal = (99, 99);
+
+        let (bfs, bfs_stats) = bfs(&grid, [(0, 0)], climb(&grid));
+        let (astar, astar_stats) = astar(
+            &grid,
+            [(0, 0)],
+            climb(&grid),
+            |(x, y)| x.abs_diff(goal.0) + y.abs_diff(goal.1),
+            |x| x == goal,
+        );
+
+        assert_eq!(astar.distance(goal), Some(198));
+        assert_eq!(bfs.distance(goal), Some(198));
+        assert!(astar_stats.expanded < bfs_stats.expanded);
+        assert_eq!(astar_stats.expanded, 199);
+    }
+
+    #[test]
+    fn uniform_grid() {
+        let size = 500;
+        let grid = Grid::filled(size, size, 0);
+
+        let begin = Instant::now();
+        let (distances, _) = bfs(&grid, [(0, 0)], climb(&grid));
+        assert!(begin.elapsed().as_secs() < 5);
+
+        assert_eq!(
+            distances.distances(),
+            &dijkstra(&grid, (0, 0), climb(&grid))
+        );
+        assert_eq!(distances.distance((size - 1, size - 2)), Some(2 * size - 3));
+    }
+
+    #[test]
+    fn snake() {
+        // Rows of `a` separated by walls of `c` with a single gap, alternating
+        // sides, so the only path zigzags through every open cell.
+        let (width, walls) = (300, 300);
+        let mut lines = vec![];
+        for wall in 0..walls {
+            lines.push("a".repeat(width));
+            let gap = if wall % 2 == 0 { width - 1 } else { 0 };
+            let mut line = "c".repeat(width);
+            line.replace_range(gap..gap + 1, "a");
+            lines.push(line);
+        }
+        lines.push("a".repeat(width));
+        let (grid, _, _) = heights(&lines);
+
+        let (distances, _) = bfs(&grid, [(0, 0)], climb(&grid));
+
+        let last = lines.len() - 1;
+        let last_x = if walls % 2 == 0 { width - 1 } else { 0 };
+        assert_eq!(
+            distances.distance((last_x, last)),
+            Some(walls * (width + 1) + width - 1)
+        );
+    }
+}
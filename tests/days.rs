@@ -0,0 +1,41 @@
+use aoc::{
+    answers,
+    days::{d01, d11, DAYS},
+    run_day, Params,
+};
+
+fn example() -> Params {
+    Params::from_args(["-e".to_string()])
+}
+
+/// The value printed for `part`, on the line starting with "Part N: ".
+fn part(answer: &str, part: u32) -> Option<&str> {
+    let label = format!("Part {part}: ");
+    answer.lines().find_map(|line| line.strip_prefix(&label))
+}
+
+#[test]
+fn examples() {
+    let params = example();
+    for (day, solve) in DAYS {
+        let answer = run_day(day, &params, |input| solve(input, &params)).unwrap();
+        let expected = answers::load(answers::path(true), day).unwrap();
+
+        for (number, expected) in [(1, expected.part1), (2, expected.part2)] {
+            if let Some(expected) = expected {
+                assert_eq!(part(&answer, number), Some(expected.as_str()), "d{day:02}");
+            }
+        }
+    }
+}
+
+#[test]
+fn settings() {
+    let params = example().with("relief", Some("none"));
+    let relieved = run_day(11, &params, |input| d11::solve(input, &params)).unwrap();
+    assert_eq!(part(&relieved, 2), Some("2713310158"));
+    assert_ne!(part(&relieved, 1), Some("10605"));
+
+    let params = Params::from_args(["-i", "input/examples/d02"].map(String::from));
+    assert!(run_day(1, &params, |input| d01::solve(input, &params)).is_err());
+}